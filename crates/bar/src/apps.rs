@@ -0,0 +1,88 @@
+//! User-defined window icons and colors from the `apps` config table.
+
+use crate::config::{AppRule, parse_color};
+use crate::logging;
+use ratatui::style::Color;
+use regex::{Regex, RegexBuilder};
+use std::sync::{LazyLock, RwLock};
+
+struct CompiledRule {
+    class: Option<Regex>,
+    title: Option<Regex>,
+    icon: Option<String>,
+    bg: Option<Color>,
+    fg: Option<Color>,
+}
+
+/// What a matching rule sets; anything left `None` falls back to the defaults
+#[derive(Debug, Default, Clone)]
+pub struct AppStyle {
+    pub icon: Option<String>,
+    pub bg: Option<Color>,
+    pub fg: Option<Color>,
+}
+
+static RULES: LazyLock<RwLock<Vec<CompiledRule>>> = LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Replace the active rules, skipping (and logging) any with invalid patterns
+pub fn configure(rules: &[AppRule]) {
+    let compiled = rules.iter().filter_map(compile).collect();
+    if let Ok(mut active) = RULES.write() {
+        *active = compiled;
+    }
+}
+
+fn compile(rule: &AppRule) -> Option<CompiledRule> {
+    if rule.class.is_none() && rule.title.is_none() {
+        logging::log_config_error("App rule needs a `class` or `title` pattern");
+        return None;
+    }
+
+    let class = match &rule.class {
+        Some(pattern) => Some(compile_pattern(pattern)?),
+        None => None,
+    };
+    let title = match &rule.title {
+        Some(pattern) => Some(compile_pattern(pattern)?),
+        None => None,
+    };
+
+    Some(CompiledRule {
+        class,
+        title,
+        icon: rule.icon.clone(),
+        bg: rule.bg.as_deref().and_then(parse_color),
+        fg: rule.fg.as_deref().and_then(parse_color),
+    })
+}
+
+fn compile_pattern(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern)
+        .case_insensitive(true)
+        .build()
+        .map_err(|e| {
+            logging::log_config_error(&format!("Invalid app pattern {}: {}", pattern, e));
+        })
+        .ok()
+}
+
+/// The first rule matching a window, if any
+pub fn lookup(class: &str, title: &str) -> Option<AppStyle> {
+    let rules = RULES.read().ok()?;
+    rules
+        .iter()
+        .find(|rule| {
+            rule.class
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(class))
+                && rule
+                    .title
+                    .as_ref()
+                    .is_none_or(|regex| regex.is_match(title))
+        })
+        .map(|rule| AppStyle {
+            icon: rule.icon.clone(),
+            bg: rule.bg,
+            fg: rule.fg,
+        })
+}
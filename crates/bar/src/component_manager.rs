@@ -1,11 +1,27 @@
+use crate::apps;
 use crate::components::{
-    Battery, Brightness, Cpu, ErrorIcon, Ram, Separator, Space, Temperature, Time, Volume, Weather,
-    Wifi, Windows, Workspaces,
+    Battery, Brightness, Cpu, ErrorIcon, Exec, Ram, Separator, Space, Temperature, Text, Time,
+    Volume, Weather, Wifi, Windows, Workspaces,
 };
-use crate::config::{ComponentConfig, Config};
+use crate::config::{
+    self, ComponentConfig, Config, LayoutConfig, SectionConstraint, WindowDisplay,
+};
+use crate::format;
+use crate::icons;
+use crate::logging;
 use crate::lua_component::{LuaComponent, LuaComponentRegistry};
-use ratatui::{prelude::Stylize, text::Span};
-use std::collections::HashMap;
+use crate::overflow::{Segment, fit_segments};
+use crate::rules::Rule;
+use crate::segments;
+use crate::states::{self, Direction};
+use ratatui::{
+    layout::Alignment,
+    prelude::Stylize,
+    style::{Color, Style},
+    text::{Line, Span},
+};
+use std::collections::{HashMap, HashSet, hash_map::Entry};
+use std::time::Duration;
 
 #[derive(Debug)]
 pub enum Component {
@@ -23,18 +39,57 @@ pub enum Component {
     Separator(Separator),
     Space(Space),
     ErrorIcon(ErrorIcon),
+    Text(Text),
+    Exec(Exec),
     Lua(LuaComponent),
 }
 
 impl Component {
+    /// `output` is the output the bar runs on, which scoped workspaces and windows follow
     pub fn new(
         component_config: &ComponentConfig,
         lua_registry: Option<&LuaComponentRegistry>,
+        output: Option<&str>,
     ) -> color_eyre::Result<Self> {
         let component_type = component_config.name();
+        if (component_config.format().is_some() || component_config.format_alt().is_some())
+            && !config::accepts_format(component_type)
+        {
+            logging::log_config_error(&format!(
+                "{component_type} doesn't support format or format_alt"
+            ));
+            return Ok(Component::ErrorIcon(ErrorIcon::new()));
+        }
         match component_type {
-            "workspaces" => Ok(Component::Workspaces(Workspaces::new())),
-            "windows" => Ok(Component::Windows(Windows::new())),
+            "workspaces" => Ok(Component::Workspaces(Workspaces::with_config(
+                component_config
+                    .persistent()
+                    .map(<[String]>::to_vec)
+                    .unwrap_or_default(),
+                component_config.labels().cloned().unwrap_or_default(),
+                component_config.show_special().unwrap_or(true),
+                component_config.window_count().unwrap_or(false),
+                component_config.scope().unwrap_or_default(),
+                output.map(str::to_string),
+            ))),
+            "windows" | "window_title" => {
+                let default_display = if component_type == "window_title" {
+                    WindowDisplay::Title
+                } else {
+                    WindowDisplay::Icons
+                };
+                let display = component_config.display().unwrap_or(default_display);
+                let title_length = component_config.title_length().unwrap_or(24);
+                let scope = component_config.scope().unwrap_or_default();
+                let group_by_workspace = component_config.group_by_workspace().unwrap_or(false);
+                Ok(Component::Windows(Windows::with_config(
+                    display,
+                    title_length,
+                    scope,
+                    group_by_workspace,
+                    output.map(str::to_string),
+                )))
+            }
             "time" => Ok(Component::Time(Time::new())),
             "weather" => Ok(Component::Weather(Weather::new())),
             "temperature" => Ok(Component::Temperature(Temperature::new())),
@@ -80,8 +135,26 @@ impl Component {
             "brightness" => Ok(Component::Brightness(Brightness::new())),
             "volume" => Ok(Component::Volume(Volume::new())),
             "battery" => Ok(Component::Battery(Battery::new()?)),
-            "separator" => Ok(Component::Separator(Separator::new())),
+            "separator" => Ok(Component::Separator(match component_config.glyph() {
+                Some(glyph) => Separator::with_separator(glyph),
+                None => Separator::new(),
+            })),
             "space" => Ok(Component::Space(Space::new())),
+            "text" => Ok(Component::Text(Text::new(
+                component_config.id().unwrap_or("text"),
+            ))),
+            "exec" => match component_config.command() {
+                Some(command) => Ok(Component::Exec(Exec::new(
+                    command,
+                    Duration::from_secs(component_config.interval().unwrap_or(10)),
+                    component_config.streaming().unwrap_or(false),
+                    component_config.json().unwrap_or(false),
+                ))),
+                None => {
+                    logging::log_config_error("exec component is missing a command");
+                    Ok(Component::ErrorIcon(ErrorIcon::new()))
+                }
+            },
             _ => {
                 // Try to load as Lua component
                 if let Some(registry) = lua_registry
@@ -152,6 +225,14 @@ impl Component {
                 // ErrorIcon doesn't need updates
                 Ok(())
             }
+            Component::Text(component) => {
+                component.update();
+                Ok(())
+            }
+            Component::Exec(component) => {
+                component.update();
+                Ok(())
+            }
             Component::Lua(component) => {
                 component.update()?;
                 Ok(())
@@ -175,10 +256,110 @@ impl Component {
             Component::Separator(component) => vec![Span::raw(component.render())],
             Component::Space(component) => vec![Span::raw(component.render())],
             Component::ErrorIcon(component) => component.render_as_spans(),
+            Component::Text(component) => component.render_as_spans(colorize),
+            Component::Exec(component) => component.render_as_spans(colorize),
             Component::Lua(component) => component.render_as_spans_with_colorize(colorize),
         }
     }
 
+    /// Named values the component exposes to format strings
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        match self {
+            Component::Workspaces(component) => component.values(),
+            Component::Windows(component) => component.values(),
+            Component::Time(component) => component.values(),
+            Component::Weather(component) => component.values(),
+            Component::Temperature(component) => component.values(),
+            Component::Cpu(component) => component.values(),
+            Component::Ram(component) => component.values(),
+            Component::Wifi(component) => component.values(),
+            Component::Brightness(component) => component.values(),
+            Component::Volume(component) => component.values(),
+            Component::Battery(component) => component.values(),
+            Component::Text(component) => component.values(),
+            Component::Exec(component) => component.values(),
+            Component::Separator(_)
+            | Component::Space(_)
+            | Component::ErrorIcon(_)
+            | Component::Lua(_) => Vec::new(),
+        }
+    }
+
+    /// Numeric value that the component's states are evaluated against
+    pub fn state_value(&self) -> Option<f64> {
+        match self {
+            // A charging battery is never in a low state
+            Component::Battery(component) if !component.is_charging => {
+                component.percentage.parse().ok()
+            }
+            Component::Temperature(component) => component.value.parse().ok(),
+            Component::Cpu(component) => component.usage.parse().ok(),
+            Component::Ram(component) => component.usage.parse().ok(),
+            Component::Brightness(component) => component.level.trim_end_matches('%').parse().ok(),
+            Component::Volume(component) => component.level.parse().ok(),
+            Component::Weather(component) => component.get_weather_data().temperature.parse().ok(),
+            Component::Exec(component) => component.percentage(),
+            _ => None,
+        }
+    }
+
+    pub fn state_direction(&self) -> Direction {
+        match self {
+            Component::Battery(_) => Direction::Falling,
+            _ => Direction::Rising,
+        }
+    }
+
+    /// Thresholds used when the config doesn't set `states`
+    pub fn default_states(&self) -> Vec<(String, f64)> {
+        match self {
+            Component::Battery(_) => vec![
+                ("warning".to_string(), 25.0),
+                ("critical".to_string(), 10.0),
+            ],
+            Component::Temperature(_) => vec![("critical".to_string(), 80.0)],
+            Component::Cpu(_) | Component::Ram(_) => vec![("critical".to_string(), 90.0)],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The state the component is currently in, if any
+    pub fn state(&self, states: &[(String, f64)]) -> Option<String> {
+        // An exec command's own class takes precedence over thresholds
+        if let Component::Exec(component) = self
+            && let Some(class) = component.class()
+        {
+            return Some(class.to_string());
+        }
+        // A battery reading that can't be parsed is a failure worth seeing, not a full battery
+        if let Component::Battery(component) = self
+            && !component.is_charging
+            && component.percentage.parse::<f64>().is_err()
+        {
+            return Some("critical".to_string());
+        }
+        let value = self.state_value()?;
+        states::evaluate(value, self.state_direction(), states).map(str::to_string)
+    }
+
+    /// Whether a component that works in the background has its first result
+    pub fn is_ready(&self) -> bool {
+        match self {
+            Component::Weather(component) => component.is_ready(),
+            Component::Exec(component) => component.is_ready(),
+            _ => true,
+        }
+    }
+
+    /// Style for urgent workspaces and windows
+    pub fn set_urgent_style(&mut self, style: Style) {
+        match self {
+            Component::Workspaces(component) => component.urgent_style = style,
+            Component::Windows(component) => component.urgent_style = style,
+            _ => {}
+        }
+    }
+
     pub fn is_muted(&self) -> bool {
         match self {
             Component::Volume(component) => component.is_muted,
@@ -197,6 +378,27 @@ impl Component {
             spans
         }
     }
+
+    /// Render the component, replacing its text with `format` when one is given
+    pub fn render_as_spans_with_format(
+        &self,
+        colorize: bool,
+        format: Option<&str>,
+    ) -> Vec<Span<'_>> {
+        let spans = self.render_as_spans_with_muting_and_colorize(colorize);
+        let Some(format) = format else {
+            return spans;
+        };
+
+        let text = match self {
+            Component::Time(_) => Time::format(format),
+            _ => format::apply(format, &self.values()),
+        };
+
+        // Only single-span components accept a format, so this keeps the style of their text
+        let style = spans.first().map(|span| span.style).unwrap_or_default();
+        vec![Span::styled(text, style)]
+    }
 }
 
 #[derive(Debug)]
@@ -204,11 +406,22 @@ pub struct ComponentManager {
     components: HashMap<String, Component>,
     config: Config,
     lua_registry: LuaComponentRegistry,
+    /// Components showing their `format_alt` after being clicked
+    alternate: HashSet<String>,
+    /// Parsed `show_when` / `hide_when` rules by component key
+    rules: HashMap<String, VisibilityRules>,
+}
+
+/// A component's visibility rules, parsed once when the config loads
+#[derive(Debug, Default)]
+struct VisibilityRules {
+    show_when: Option<Rule>,
+    hide_when: Option<Rule>,
 }
 
 impl ComponentManager {
-    pub fn new() -> color_eyre::Result<Self> {
-        let config = Config::load()?;
+    pub fn new(profile: Option<&str>, output: Option<&str>) -> color_eyre::Result<Self> {
+        let config = Config::load(profile, output)?;
         let mut lua_registry = LuaComponentRegistry::new();
 
         // Load Lua components from config directory
@@ -219,33 +432,92 @@ impl ComponentManager {
                 .join("components");
         lua_registry.load_from_directory(config_dir.to_str().unwrap())?;
 
+        let components = Self::create_components(&config, &lua_registry)?;
+        let rules = Self::parse_rules(&config);
+
+        Ok(Self {
+            components,
+            config,
+            lua_registry,
+            alternate: HashSet::new(),
+            rules,
+        })
+    }
+
+    /// Create one component per distinct configuration across all sections
+    fn create_components(
+        config: &Config,
+        lua_registry: &LuaComponentRegistry,
+    ) -> color_eyre::Result<HashMap<String, Component>> {
         let mut components = HashMap::new();
+        icons::configure(&config.icons);
+        apps::configure(&config.apps);
 
         // Create all components (unknown ones become error icons)
-        for component_config in &config.bars.left {
-            let component = Component::new(component_config, Some(&lua_registry))?;
-            components.insert(component_config.name().to_string(), component);
-        }
-
-        for component_config in &config.bars.middle {
-            if !components.contains_key(component_config.name()) {
-                let component = Component::new(component_config, Some(&lua_registry))?;
-                components.insert(component_config.name().to_string(), component);
+        for component_config in config
+            .bars
+            .sections
+            .values()
+            .flatten()
+            .flat_map(ComponentConfig::leaves)
+        {
+            if let Entry::Vacant(entry) = components.entry(component_config.key()) {
+                // A component that can't start (e.g. no battery) shows as an error icon
+                let mut component = Component::new(
+                    component_config,
+                    Some(lua_registry),
+                    config.active_output.as_deref(),
+                )
+                .unwrap_or_else(|e| {
+                    logging::log_component_error(component_config.name(), &format!("{}", e));
+                    Component::ErrorIcon(ErrorIcon::new())
+                });
+                component.set_urgent_style(config.state_style("urgent"));
+                entry.insert(component);
             }
         }
 
-        for component_config in &config.bars.right {
-            if !components.contains_key(component_config.name()) {
-                let component = Component::new(component_config, Some(&lua_registry))?;
-                components.insert(component_config.name().to_string(), component);
+        Ok(components)
+    }
+
+    /// Parse every component's visibility rules, reporting the ones that fail to parse
+    fn parse_rules(config: &Config) -> HashMap<String, VisibilityRules> {
+        let parse = |component_config: &ComponentConfig, rule: Option<&str>| {
+            Rule::parse(rule?)
+                .map_err(|e| {
+                    logging::log_config_error(&format!(
+                        "Invalid rule for {}: {}",
+                        component_config.name(),
+                        e
+                    ))
+                })
+                .ok()
+        };
+
+        let mut rules = HashMap::new();
+        for component_config in config
+            .bars
+            .sections
+            .values()
+            .flatten()
+            .flat_map(ComponentConfig::leaves)
+        {
+            if component_config.show_when().is_none() && component_config.hide_when().is_none() {
+                continue;
+            }
+            if let Entry::Vacant(entry) = rules.entry(component_config.key()) {
+                entry.insert(VisibilityRules {
+                    show_when: parse(component_config, component_config.show_when()),
+                    hide_when: parse(component_config, component_config.hide_when()),
+                });
             }
         }
+        rules
+    }
 
-        Ok(Self {
-            components,
-            config,
-            lua_registry,
-        })
+    /// Whether every background component has its first result
+    pub fn is_ready(&self) -> bool {
+        self.components.values().all(Component::is_ready)
     }
 
     pub fn update(&mut self) -> color_eyre::Result<()> {
@@ -261,17 +533,279 @@ impl ComponentManager {
         if let Some(component_configs) = self.config.get_components_for_bar(bar) {
             component_configs
                 .iter()
-                .filter_map(|config| self.components.get(config.name()))
+                .flat_map(ComponentConfig::leaves)
+                .filter_map(|config| self.components.get(&config.key()))
                 .collect()
         } else {
             Vec::new()
         }
     }
 
+    /// Render a bar section's components as segments, fitted into `width` cells
+    pub fn get_bar_segments(
+        &self,
+        bar: &str,
+        width: u16,
+        alignment: Alignment,
+    ) -> Vec<Segment<'_>> {
+        let Some(component_configs) = self.config.get_components_for_bar(bar) else {
+            return Vec::new();
+        };
+
+        // Hidden components leave no slot behind; a group vanishes with its last member
+        let segments = component_configs
+            .iter()
+            .filter_map(|config| {
+                let key = config.key();
+                let (spans, background) = match config {
+                    ComponentConfig::Group(group) => {
+                        let leaves: Vec<_> = config
+                            .leaves()
+                            .into_iter()
+                            .filter(|leaf| self.is_visible(leaf))
+                            .collect();
+                        if leaves.is_empty() {
+                            return None;
+                        }
+
+                        let style = group.style.to_style();
+                        let alternate = self.alternate.contains(&key);
+                        let spans = leaves
+                            .into_iter()
+                            .flat_map(|leaf| self.render_component(leaf, alternate))
+                            .collect();
+                        let spans =
+                            segments::decorate(spans, style, &self.config.segments, alignment);
+                        (spans, Some(style.bg.unwrap_or(Color::Reset)))
+                    }
+                    _ if !self.is_visible(config) => return None,
+                    _ => {
+                        let alternate = self.alternate.contains(&key);
+                        (self.render_component(config, alternate), None)
+                    }
+                };
+
+                Some(Segment {
+                    key,
+                    spans,
+                    priority: config.priority(),
+                    min_width: config.min_width(),
+                    max_width: config.max_width(),
+                    background,
+                })
+            })
+            .collect();
+
+        let mut segments = fit_segments(segments, width);
+        segments::link_transitions(&mut segments, &self.config.segments, alignment);
+        segments
+    }
+
+    /// Render a bar section's components, fitted into `width` cells
+    pub fn get_bar_spans(&self, bar: &str, width: u16, alignment: Alignment) -> Vec<Span<'_>> {
+        self.get_bar_segments(bar, width, alignment)
+            .into_iter()
+            .flat_map(|segment| segment.spans)
+            .collect()
+    }
+
+    /// Render one configured component, honouring its format strings
+    fn render_component(&self, config: &ComponentConfig, alternate: bool) -> Vec<Span<'_>> {
+        let Some(component) = self.components.get(&config.key()) else {
+            return Vec::new();
+        };
+
+        let alternate = alternate || self.alternate.contains(&config.key());
+        let format = match config.format_alt() {
+            Some(format_alt) if alternate => Some(format_alt),
+            _ => config.format(),
+        };
+
+        let colorize = self.get_colorize();
+        let spans = component.render_as_spans_with_format(colorize, format);
+        if !colorize {
+            return spans;
+        }
+
+        match Self::component_state(config, component) {
+            Some(state) => {
+                let style = self.config.state_style(&state);
+                spans
+                    .into_iter()
+                    .map(|span| span.patch_style(style))
+                    .collect()
+            }
+            None => spans,
+        }
+    }
+
+    /// The component's current state, from its configured or default thresholds
+    fn component_state(config: &ComponentConfig, component: &Component) -> Option<String> {
+        let states = config
+            .states()
+            .unwrap_or_else(|| component.default_states());
+        component.state(&states)
+    }
+
+    /// Whether a component passes its `show_when` and `hide_when` rules;
+    /// rules that fail to parse are ignored
+    fn is_visible(&self, config: &ComponentConfig) -> bool {
+        let Some(component) = self.components.get(&config.key()) else {
+            return true;
+        };
+        // Text components stay hidden until something is pushed to them, exec ones while
+        // their command prints nothing
+        match component {
+            Component::Text(text) if text.content.text.is_empty() => return false,
+            Component::Exec(exec) if exec.text().is_empty() => return false,
+            _ => {}
+        }
+        let Some(rules) = self.rules.get(&config.key()) else {
+            return true;
+        };
+
+        let mut values = component.values();
+        values.push((
+            "threshold",
+            Self::component_state(config, component).unwrap_or_default(),
+        ));
+
+        let shown = rules
+            .show_when
+            .as_ref()
+            .is_none_or(|rule| rule.evaluate(&values));
+        let hidden = rules
+            .hide_when
+            .as_ref()
+            .is_some_and(|rule| rule.evaluate(&values));
+        shown && !hidden
+    }
+
+    /// Toggle the alternate format of the component or segment identified by `key`
+    pub fn on_click(&mut self, key: &str) {
+        if !self.alternate.remove(key) {
+            self.alternate.insert(key.to_string());
+        }
+    }
+
+    /// Width in cells that a bar section's components currently occupy
+    pub fn get_bar_width(&self, bar: &str) -> u16 {
+        let spans = self.get_bar_spans(bar, u16::MAX, Alignment::Left);
+        Line::from(spans).width().try_into().unwrap_or(u16::MAX)
+    }
+
+    pub fn get_bar_layout(&self, bar: &str) -> SectionConstraint {
+        self.config.get_layout_for_bar(bar)
+    }
+
+    pub fn get_layout(&self) -> &LayoutConfig {
+        &self.config.layout
+    }
+
     pub fn get_colorize(&self) -> bool {
         self.config.colorize
     }
 
+    /// Turn colors on or off until the next reload, toggling when `colorize` is `None`
+    pub fn set_colorize(&mut self, colorize: Option<bool>) -> bool {
+        self.config.colorize = colorize.unwrap_or(!self.config.colorize);
+        self.config.colorize
+    }
+
+    /// How many distinct `text` components are configured with `id`
+    pub fn text_components(&self, id: &str) -> usize {
+        self.config
+            .bars
+            .sections
+            .values()
+            .flatten()
+            .flat_map(ComponentConfig::leaves)
+            .filter(|config| config.name() == "text" && config.id().unwrap_or("text") == id)
+            .map(ComponentConfig::key)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// Recreate and update every component named or keyed `name`, skipping its update interval
+    /// Returns how many components were refreshed
+    pub fn refresh(&mut self, name: &str) -> color_eyre::Result<usize> {
+        let configs: Vec<ComponentConfig> = self
+            .config
+            .bars
+            .sections
+            .values()
+            .flatten()
+            .flat_map(ComponentConfig::leaves)
+            .filter(|config| config.name() == name || config.key() == name)
+            .cloned()
+            .collect();
+
+        let mut refreshed = HashSet::new();
+        for config in configs {
+            let key = config.key();
+            if !refreshed.insert(key.clone()) {
+                continue;
+            }
+            let mut component = Component::new(
+                &config,
+                Some(&self.lua_registry),
+                self.config.active_output.as_deref(),
+            )?;
+            component.set_urgent_style(self.config.state_style("urgent"));
+            component.update()?;
+            self.components.insert(key, component);
+        }
+
+        Ok(refreshed.len())
+    }
+
+    /// Every configured component's values and rendered text, for debugging
+    pub fn dump_state(&self) -> serde_json::Value {
+        let mut seen = HashSet::new();
+        let components: Vec<_> = self
+            .config
+            .bars
+            .sections
+            .iter()
+            .flat_map(|(bar, configs)| {
+                configs
+                    .iter()
+                    .flat_map(ComponentConfig::leaves)
+                    .map(move |config| (bar, config))
+            })
+            .filter(|(_, config)| seen.insert(config.key()))
+            .filter_map(|(bar, config)| {
+                let component = self.components.get(&config.key())?;
+                let text: String = self
+                    .render_component(config, self.alternate.contains(&config.key()))
+                    .iter()
+                    .map(|span| span.content.as_ref())
+                    .collect();
+                let values: serde_json::Map<_, _> = component
+                    .values()
+                    .into_iter()
+                    .map(|(name, value)| (name.to_string(), value.into()))
+                    .collect();
+
+                Some(serde_json::json!({
+                    "key": config.key(),
+                    "name": config.name(),
+                    "bar": bar,
+                    "visible": self.is_visible(config),
+                    "state": Self::component_state(config, component),
+                    "text": text,
+                    "values": values,
+                }))
+            })
+            .collect();
+
+        serde_json::json!({
+            "colorize": self.config.colorize,
+            "output": self.config.active_output,
+            "components": components,
+        })
+    }
+
     pub fn reload(&mut self) -> color_eyre::Result<()> {
         let new_config = self.config.reload()?;
 
@@ -285,30 +819,151 @@ impl ComponentManager {
         self.lua_registry
             .load_from_directory(config_dir.to_str().unwrap())?;
 
-        let mut components = HashMap::new();
+        let components = Self::create_components(&new_config, &self.lua_registry)?;
 
-        // Create all components (unknown ones become error icons)
-        for component_config in &new_config.bars.left {
-            let component = Component::new(component_config, Some(&self.lua_registry))?;
-            components.insert(component_config.name().to_string(), component);
+        self.rules = Self::parse_rules(&new_config);
+        self.config = new_config;
+        self.components = components;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::text;
+    use serde_json::json;
+
+    /// A manager for a bar with `left` as its only section
+    fn manager(left: serde_json::Value) -> ComponentManager {
+        manager_for(json!({ "bars": { "left": left } }))
+    }
+
+    fn manager_for(config: serde_json::Value) -> ComponentManager {
+        let config: Config = serde_json::from_value(config).unwrap();
+        let lua_registry = LuaComponentRegistry::new();
+        ComponentManager {
+            components: ComponentManager::create_components(&config, &lua_registry).unwrap(),
+            rules: ComponentManager::parse_rules(&config),
+            config,
+            lua_registry,
+            alternate: HashSet::new(),
         }
+    }
+
+    fn visible(manager: &ComponentManager, index: usize) -> bool {
+        manager.is_visible(&manager.config.bars.sections["left"][index])
+    }
 
-        for component_config in &new_config.bars.middle {
-            if !components.contains_key(component_config.name()) {
-                let component = Component::new(component_config, Some(&self.lua_registry))?;
-                components.insert(component_config.name().to_string(), component);
+    #[tokio::test]
+    async fn text_components_are_hidden_until_set() {
+        let mut manager = manager(json!([
+            { "name": "text", "id": "visibility-test" },
+            { "name": "text", "id": "visibility-test-rule", "hide_when": "text == done" },
+        ]));
+        assert!(!visible(&manager, 0));
+
+        text::set("visibility-test", "hello".to_string(), None, None, None);
+        text::set("visibility-test-rule", "done".to_string(), None, None, None);
+        manager.update().unwrap();
+        assert!(visible(&manager, 0));
+        assert!(!visible(&manager, 1));
+    }
+
+    #[tokio::test]
+    async fn exec_components_are_hidden_while_their_command_prints_nothing() {
+        let mut manager = manager(json!([
+            { "name": "exec", "command": "true" },
+            { "name": "exec", "command": "echo hello" },
+        ]));
+
+        // The commands run in the background
+        for _ in 0..50 {
+            manager.update().unwrap();
+            if visible(&manager, 1) {
+                break;
             }
+            tokio::time::sleep(Duration::from_millis(100)).await;
         }
+        assert!(!visible(&manager, 0));
+        assert!(visible(&manager, 1));
+    }
 
-        for component_config in &new_config.bars.right {
-            if !components.contains_key(component_config.name()) {
-                let component = Component::new(component_config, Some(&self.lua_registry))?;
-                components.insert(component_config.name().to_string(), component);
-            }
+    #[test]
+    fn text_components_are_counted_by_id() {
+        let manager = manager(json!([
+            { "name": "text", "id": "deploy" },
+            { "name": "text", "id": "deploy", "hide_when": "text == ok" },
+            { "name": "text", "id": "other" },
+            "text",
+        ]));
+        assert_eq!(manager.text_components("deploy"), 2);
+        assert_eq!(manager.text_components("text"), 1);
+        assert_eq!(manager.text_components("missing"), 0);
+    }
+
+    #[tokio::test]
+    async fn clicking_toggles_the_alternate_format() {
+        let mut manager = manager(json!([{
+            "name": "text",
+            "id": "format-test",
+            "format": "[{text}]",
+            "format_alt": "{text}: {tooltip}",
+        }]));
+        text::set(
+            "format-test",
+            "up".to_string(),
+            None,
+            Some("3 hosts".to_string()),
+            None,
+        );
+        manager.update().unwrap();
+        let rendered = |manager: &ComponentManager| {
+            let spans = manager.get_bar_spans("left", 200, Alignment::Left);
+            spans
+                .iter()
+                .map(|span| span.content.to_string())
+                .collect::<String>()
+        };
+
+        assert_eq!(rendered(&manager), "[up]");
+        let key = manager.config.bars.sections["left"][0].key();
+        manager.on_click(&key);
+        assert_eq!(rendered(&manager), "up: 3 hosts");
+        manager.on_click(&key);
+        assert_eq!(rendered(&manager), "[up]");
+    }
+
+    #[test]
+    fn multi_label_components_reject_a_format() {
+        let manager = manager(json!([
+            { "name": "workspaces", "format": "{active}" },
+            { "name": "separator", "format_alt": "|" },
+        ]));
+        for config in &manager.config.bars.sections["left"] {
+            assert!(matches!(
+                manager.components[&config.key()],
+                Component::ErrorIcon(_)
+            ));
         }
+    }
 
-        self.config = new_config;
-        self.components = components;
-        Ok(())
+    #[test]
+    fn transitions_link_across_hidden_and_empty_groups() {
+        let manager = manager_for(json!({
+            "bars": { "left": [
+                { "segment": ["separator"], "bg": "red" },
+                { "segment": [{ "name": "text", "id": "transition-test" }], "bg": "green" },
+                { "segment": [], "bg": "yellow" },
+                { "segment": ["separator"], "bg": "blue" },
+            ] },
+            "segments": { "style": "arrow" },
+        }));
+
+        let segments = manager.get_bar_segments("left", 200, Alignment::Left);
+        assert_eq!(segments.len(), 2);
+        let arrow = segments[0].spans.last().unwrap();
+        assert_eq!(arrow.style.fg, Some(Color::Red));
+        assert_eq!(arrow.style.bg, Some(Color::Blue));
     }
 }
@@ -0,0 +1,82 @@
+use crate::component_manager::ComponentManager;
+use ratatui::{
+    Frame,
+    layout::{Alignment, Rect},
+    prelude::Stylize,
+    style::Color,
+    text::Line,
+    widgets::Paragraph,
+};
+
+/// Screen area occupied by a rendered component or segment group
+#[derive(Debug, Clone)]
+pub struct ClickRegion {
+    pub area: Rect,
+    pub key: String,
+}
+
+/// A named section of the bar, rendering its components on a single line
+#[derive(Debug)]
+pub struct BarSection<'a> {
+    name: &'a str,
+    alignment: Alignment,
+}
+
+impl<'a> BarSection<'a> {
+    pub fn new(name: &'a str, alignment: Alignment) -> Self {
+        Self { name, alignment }
+    }
+
+    /// Render the section and return where each of its components ended up
+    pub fn render(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        component_manager: &ComponentManager,
+    ) -> Vec<ClickRegion> {
+        let segments = component_manager.get_bar_segments(self.name, area.width, self.alignment);
+
+        if segments.is_empty() {
+            return Vec::new();
+        }
+
+        let widths: Vec<u16> = segments
+            .iter()
+            .map(|segment| {
+                let width: usize = segment.spans.iter().map(|span| span.width()).sum();
+                width as u16
+            })
+            .collect();
+        let total: u16 = widths.iter().sum();
+
+        // Mirror the offset Paragraph applies for the alignment
+        let mut x = area.x
+            + match self.alignment {
+                Alignment::Left => 0,
+                Alignment::Center => area.width.saturating_sub(total) / 2,
+                Alignment::Right => area.width.saturating_sub(total),
+            };
+
+        let mut regions = Vec::new();
+        let mut spans = Vec::new();
+        for (segment, width) in segments.into_iter().zip(widths) {
+            regions.push(ClickRegion {
+                area: Rect::new(x, area.y, width, 1).intersection(area),
+                key: segment.key,
+            });
+            x += width;
+            spans.extend(segment.spans);
+        }
+
+        let line = Line::from(spans);
+
+        frame.render_widget(
+            Paragraph::new(line)
+                .alignment(self.alignment)
+                .fg(Color::White),
+            area,
+        );
+
+        regions
+    }
+}
@@ -1,3 +1,4 @@
+use crate::icons;
 use crate::logging;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use std::time::{Duration, Instant};
@@ -33,8 +34,7 @@ impl Battery {
 
         let is_charging = matches!(battery.state(), battery::State::Charging);
         let percentage = ((battery.state_of_charge().value * 100.0) as i32).to_string();
-        let icon = if is_charging { "󰂄" } else { "󰁹" };
-        let cached_span_content = format!("{} {}%", icon, percentage);
+        let cached_span_content = format!("{} {}%", Self::icon(is_charging), percentage);
 
         Ok(Self {
             percentage,
@@ -55,31 +55,36 @@ impl Battery {
             self.is_charging = matches!(self.battery.state(), battery::State::Charging);
 
             // Update cached span content
-            let icon = if self.is_charging { "󰂄" } else { "󰁹" };
-            self.cached_span_content = format!("{} {}%", icon, self.percentage);
+            self.cached_span_content =
+                format!("{} {}%", Self::icon(self.is_charging), self.percentage);
 
             self.last_update = now;
         }
         Ok(())
     }
 
+    fn icon(is_charging: bool) -> String {
+        if is_charging {
+            icons::icon("battery.charging")
+        } else {
+            icons::icon("battery.discharging")
+        }
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", Self::icon(self.is_charging)),
+            ("percent", self.percentage.clone()),
+            ("charging", self.is_charging.to_string()),
+            ("state", self.battery.state().to_string()),
+        ]
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let span = Span::raw(&self.cached_span_content);
         if colorize {
-            let color = if self.is_charging {
-                Color::Green
-            } else if let Ok(percentage) = self.percentage.parse::<u32>() {
-                if percentage <= 10 {
-                    Color::Red // Very low: Red
-                } else if percentage <= 25 {
-                    Color::Yellow // Low: Yellow/Amber  
-                } else {
-                    Color::Green // Normal/High: Green
-                }
-            } else {
-                Color::Red
-            };
-            vec![span.fg(color)]
+            // Low charge is styled through the component's states
+            vec![span.fg(Color::Green)]
         } else {
             vec![span]
         }
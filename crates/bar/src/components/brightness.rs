@@ -1,3 +1,4 @@
+use crate::icons;
 use crate::logging;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use regex::Regex;
@@ -21,7 +22,7 @@ impl Default for Brightness {
 impl Brightness {
     pub fn new() -> Self {
         let level = get_system_brightness().unwrap_or_default();
-        let cached_span_content = format!("󰃠 {}", level);
+        let cached_span_content = format!("{} {}", icons::icon("brightness"), level);
         Self {
             level,
             cached_span_content,
@@ -30,7 +31,14 @@ impl Brightness {
 
     pub fn update(&mut self) {
         self.level = get_system_brightness().unwrap_or_default();
-        self.cached_span_content = format!("󰃠 {}", self.level);
+        self.cached_span_content = format!("{} {}", icons::icon("brightness"), self.level);
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", icons::icon("brightness")),
+            ("percent", self.level.trim_end_matches('%').to_string()),
+        ]
     }
 
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
@@ -44,16 +52,23 @@ impl Brightness {
 }
 
 fn get_system_brightness() -> Option<String> {
-    let output = Command::new("brightnessctl")
-        .output()
-        .expect("failed to get brightness");
+    let output = match Command::new("brightnessctl").output() {
+        Ok(output) => output,
+        Err(e) => {
+            logging::log_component_error(
+                "BRIGHTNESS",
+                &format!("Failed to run brightnessctl: {}", e),
+            );
+            return None;
+        }
+    };
 
     if output.status.success() {
-        let brightness_str = str::from_utf8(&output.stdout).unwrap();
+        let brightness_str = String::from_utf8_lossy(&output.stdout);
 
         let re = &BRIGHTNESS_REGEX;
 
-        if let Some(brightness) = re.find(brightness_str).map(|m| m.as_str()) {
+        if let Some(brightness) = re.find(&brightness_str).map(|m| m.as_str()) {
             return Some(brightness.to_string());
         }
 
@@ -1,3 +1,5 @@
+use crate::icons;
+
 #[derive(Debug, Default, Clone)]
 pub struct ErrorIcon;
 
@@ -8,7 +10,7 @@ impl ErrorIcon {
 
     pub fn render_as_spans(&self) -> Vec<ratatui::text::Span<'_>> {
         vec![ratatui::text::Span::styled(
-            "  ",
+            format!(" {} ", icons::icon("error")),
             ratatui::style::Style::default(), // .fg(ratatui::style::Color::Yellow),
         )]
     }
@@ -0,0 +1,262 @@
+use crate::config::parse_color;
+use crate::logging;
+use ratatui::{prelude::Stylize, text::Span};
+use serde::Deserialize;
+use std::process::Stdio;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::process::Command;
+
+/// What the command last printed
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ExecOutput {
+    #[serde(default)]
+    pub text: String,
+    /// Text color, as in `theme`
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Theme entry to style the text with, overriding `states`
+    #[serde(default)]
+    pub class: Option<String>,
+    /// Value `states` thresholds are checked against
+    #[serde(default)]
+    pub percentage: Option<f64>,
+}
+
+/// Shortest time between runs, so `"interval": 0` doesn't spin
+const MIN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Longest wait before restarting a streaming command that keeps exiting
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(300);
+
+impl ExecOutput {
+    /// Read the command's output: its first line, or all of it as JSON when `json` is set
+    fn parse(output: &str, json: bool) -> Self {
+        if !json {
+            return Self {
+                text: output.lines().next().unwrap_or_default().trim().to_string(),
+                ..Self::default()
+            };
+        }
+        let output = output.trim();
+
+        serde_json::from_str(output).unwrap_or_else(|e| {
+            logging::log_component_error("EXEC", &format!("Invalid JSON output: {}", e));
+            Self::default()
+        })
+    }
+}
+
+#[derive(Debug)]
+pub struct Exec {
+    output: Arc<Mutex<ExecOutput>>,
+    current: ExecOutput,
+    /// Whether the command has finished its first run, or printed its first line
+    ready: Arc<AtomicBool>,
+    _update_handle: tokio::task::JoinHandle<()>,
+}
+
+impl Exec {
+    /// Run `command` with `sh -c` every `interval`, or once and read each line it prints
+    /// when `streaming`
+    pub fn new(command: &str, interval: Duration, streaming: bool, json: bool) -> Self {
+        let output = Arc::new(Mutex::new(ExecOutput::default()));
+        let output_clone = output.clone();
+        let ready = Arc::new(AtomicBool::new(false));
+        let ready_clone = ready.clone();
+        let command = command.to_string();
+        let interval = interval.max(MIN_INTERVAL);
+
+        let update_handle = tokio::spawn(async move {
+            let mut delay = interval;
+            loop {
+                let started = Instant::now();
+                let result = if streaming {
+                    stream_command(&command, json, &output_clone, &ready_clone).await
+                } else {
+                    run_command(&command, json, &output_clone).await
+                };
+                ready_clone.store(true, Ordering::Relaxed);
+                if let Err(e) = result {
+                    logging::log_component_error("EXEC", &format!("{}: {}", command, e));
+                }
+
+                // Streaming commands are restarted after the interval if they exit, backing
+                // off while they keep exiting straight away
+                if streaming {
+                    delay = if started.elapsed() < delay {
+                        (delay * 2).min(MAX_RESTART_DELAY.max(interval))
+                    } else {
+                        interval
+                    };
+                }
+                tokio::time::sleep(delay).await;
+            }
+        });
+
+        Self {
+            output,
+            current: ExecOutput::default(),
+            ready,
+            _update_handle: update_handle,
+        }
+    }
+
+    pub fn update(&mut self) {
+        if let Ok(output) = self.output.lock() {
+            self.current = output.clone();
+        }
+    }
+
+    pub fn is_ready(&self) -> bool {
+        self.ready.load(Ordering::Relaxed)
+    }
+
+    pub fn text(&self) -> &str {
+        &self.current.text
+    }
+
+    pub fn class(&self) -> Option<&str> {
+        self.current.class.as_deref()
+    }
+
+    pub fn percentage(&self) -> Option<f64> {
+        self.current.percentage
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("text", self.current.text.clone()),
+            ("class", self.current.class.clone().unwrap_or_default()),
+            (
+                "percentage",
+                self.current
+                    .percentage
+                    .map(|percentage| percentage.to_string())
+                    .unwrap_or_default(),
+            ),
+        ]
+    }
+
+    pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
+        if self.current.text.is_empty() {
+            return Vec::new();
+        }
+
+        let span = Span::raw(&self.current.text);
+        match self.current.color.as_deref().and_then(parse_color) {
+            Some(color) if colorize => vec![span.fg(color)],
+            _ => vec![span],
+        }
+    }
+}
+
+impl Drop for Exec {
+    fn drop(&mut self) {
+        // Stop the command when the component is replaced on reload
+        self._update_handle.abort();
+    }
+}
+
+/// Run the command to completion and show what it printed
+/// A command that fails is reported, and what it printed is styled `critical` unless it
+/// chose a class itself
+async fn run_command(
+    command: &str,
+    json: bool,
+    output: &Mutex<ExecOutput>,
+) -> color_eyre::Result<()> {
+    let result = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .output()
+        .await?;
+
+    let mut parsed = ExecOutput::parse(&String::from_utf8_lossy(&result.stdout), json);
+    if !result.status.success() {
+        parsed.class.get_or_insert_with(|| "critical".to_string());
+    }
+    if let Ok(mut output) = output.lock() {
+        *output = parsed;
+    }
+
+    if !result.status.success() {
+        let stderr = String::from_utf8_lossy(&result.stderr);
+        return Err(color_eyre::eyre::eyre!(
+            "{} {}",
+            result.status,
+            stderr.lines().next().unwrap_or_default()
+        ));
+    }
+    Ok(())
+}
+
+/// Run the command and show each line it prints until it exits
+async fn stream_command(
+    command: &str,
+    json: bool,
+    output: &Mutex<ExecOutput>,
+    ready: &AtomicBool,
+) -> color_eyre::Result<()> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let Some(stdout) = child.stdout.take() else {
+        return Ok(());
+    };
+    let mut lines = BufReader::new(stdout).lines();
+    while let Some(line) = lines.next_line().await? {
+        let parsed = ExecOutput::parse(&line, json);
+        if let Ok(mut output) = output.lock() {
+            *output = parsed;
+        }
+        ready.store(true, Ordering::Relaxed);
+    }
+
+    let status = child.wait().await?;
+    if !status.success() {
+        return Err(color_eyre::eyre::eyre!("{}", status));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_output_shows_its_first_line() {
+        let output = ExecOutput::parse("  12 updates \nsecond line\n", false);
+        assert_eq!(output.text, "12 updates");
+        assert_eq!(ExecOutput::parse("", false).text, "");
+    }
+
+    #[test]
+    fn json_output_may_span_lines() {
+        let output = ExecOutput::parse("{\n  \"text\": \"up\",\n  \"class\": \"good\"\n}\n", true);
+        assert_eq!(output.text, "up");
+        assert_eq!(output.class.as_deref(), Some("good"));
+    }
+
+    #[tokio::test]
+    async fn failing_commands_are_reported_and_marked_critical() {
+        let output = Mutex::new(ExecOutput::default());
+        let result = run_command("echo partial; echo oops >&2; exit 3", false, &output).await;
+
+        assert!(result.unwrap_err().to_string().contains("oops"));
+        let output = output.lock().unwrap();
+        assert_eq!(output.text, "partial");
+        assert_eq!(output.class.as_deref(), Some("critical"));
+    }
+}
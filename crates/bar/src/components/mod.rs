@@ -1,15 +1,15 @@
+pub mod bar_section;
 pub mod battery;
 pub mod brightness;
 pub mod cpu;
 pub mod error_icon;
-pub mod left_bar;
-pub mod middle_bar;
+pub mod exec;
 pub mod ram;
-pub mod right_bar;
 pub mod separator;
 pub mod space;
 pub mod sparkline;
 pub mod temperature;
+pub mod text;
 pub mod time;
 pub mod volume;
 pub mod weather;
@@ -17,17 +17,17 @@ pub mod wifi;
 pub mod windows;
 pub mod workspaces;
 
+pub use bar_section::{BarSection, ClickRegion};
 pub use battery::Battery;
 pub use brightness::Brightness;
 pub use cpu::Cpu;
 pub use error_icon::ErrorIcon;
-pub use left_bar::LeftBar;
-pub use middle_bar::MiddleBar;
+pub use exec::Exec;
 pub use ram::Ram;
-pub use right_bar::RightBar;
 pub use separator::Separator;
 pub use space::Space;
 pub use temperature::Temperature;
+pub use text::Text;
 pub use time::Time;
 pub use volume::Volume;
 pub use weather::Weather;
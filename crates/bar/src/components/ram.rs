@@ -1,4 +1,5 @@
 use super::sparkline::Sparkline;
+use crate::icons;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use std::time::{Duration, Instant};
 use sysinfo::{MemoryRefreshKind, RefreshKind};
@@ -27,9 +28,9 @@ impl Ram {
         let usage = "0".to_string();
         let sparkline = Sparkline::new(sparkline, sparkline_length, sparkline_logarithmic);
         let cached_span_content = if sparkline.enabled {
-            format!("󰍛 {}", sparkline.render_with_spaces())
+            format!("{} {}", icons::icon("ram"), sparkline.render_with_spaces())
         } else {
-            format!("󰍛 {}%", usage)
+            format!("{} {}%", icons::icon("ram"), usage)
         };
 
         Self {
@@ -57,28 +58,29 @@ impl Ram {
                 self.sparkline.update(mem_percent as u64);
 
                 // Render sparkline
-                self.cached_span_content = format!("󰍛 {}", self.sparkline.render());
+                self.cached_span_content =
+                    format!("{} {}", icons::icon("ram"), self.sparkline.render());
             } else {
-                self.cached_span_content = format!("󰍛 {}%", self.usage);
+                self.cached_span_content = format!("{} {}%", icons::icon("ram"), self.usage);
             }
 
             self.last_update = now;
         }
     }
 
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", icons::icon("ram")),
+            ("percent", self.usage.clone()),
+            ("sparkline", self.sparkline.render_with_spaces()),
+        ]
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let span = Span::raw(&self.cached_span_content);
         if colorize {
-            let color = if let Ok(usage) = self.usage.parse::<u32>() {
-                if usage >= 90 {
-                    Color::Red // High RAM usage: Red
-                } else {
-                    Color::Green // Normal: Green
-                }
-            } else {
-                Color::Green
-            };
-            vec![span.fg(color)]
+            // High usage is styled through the component's states
+            vec![span.fg(Color::Green)]
         } else {
             vec![span]
         }
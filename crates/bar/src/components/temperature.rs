@@ -1,3 +1,4 @@
+use crate::icons;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use std::time::{Duration, Instant};
 use sysinfo::Components;
@@ -21,7 +22,7 @@ impl Temperature {
     pub fn new() -> Self {
         let components = Components::new();
         let value = "0".to_string();
-        let cached_span_content = format!(" {}°C", value);
+        let cached_span_content = format!("{} {}°C", icons::icon("temperature"), value);
 
         Self {
             value,
@@ -44,26 +45,26 @@ impl Temperature {
             }) && let Some(temp) = component.temperature()
             {
                 self.value = format!("{:.0}", temp);
-                self.cached_span_content = format!(" {}°C", self.value);
+                self.cached_span_content =
+                    format!("{} {}°C", icons::icon("temperature"), self.value);
             }
 
             self.last_update = now;
         }
     }
 
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", icons::icon("temperature")),
+            ("temp", self.value.clone()),
+        ]
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let span = Span::raw(&self.cached_span_content);
         if colorize {
-            let color = if let Ok(temp) = self.value.parse::<u32>() {
-                if temp >= 80 {
-                    Color::Red // High temp: Red
-                } else {
-                    Color::Yellow // Normal: Yellow
-                }
-            } else {
-                Color::Yellow
-            };
-            vec![span.fg(color)]
+            // High temperatures are styled through the component's states
+            vec![span.fg(Color::Yellow)]
         } else {
             vec![span]
         }
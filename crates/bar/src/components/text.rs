@@ -0,0 +1,92 @@
+use crate::config::parse_color;
+use ratatui::{prelude::Stylize, text::Span};
+use std::collections::HashMap;
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, Instant};
+
+/// Content pushed to a `text` component from outside the bar
+#[derive(Debug, Clone, Default)]
+pub struct TextContent {
+    pub text: String,
+    pub color: Option<String>,
+    pub tooltip: Option<String>,
+    /// When the content is cleared again, if ever
+    pub expires: Option<Instant>,
+}
+
+/// Pushed content by component id; kept outside the components so it survives reloads
+static CONTENTS: LazyLock<RwLock<HashMap<String, TextContent>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+/// Set the content of every `text` component with `id`, clearing it after `expire`
+pub fn set(
+    id: &str,
+    text: String,
+    color: Option<String>,
+    tooltip: Option<String>,
+    expire: Option<Duration>,
+) {
+    if let Ok(mut contents) = CONTENTS.write() {
+        contents.insert(
+            id.to_string(),
+            TextContent {
+                text,
+                color,
+                tooltip,
+                expires: expire.map(|expire| Instant::now() + expire),
+            },
+        );
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct Text {
+    pub id: String,
+    pub content: TextContent,
+}
+
+impl Text {
+    pub fn new(id: &str) -> Self {
+        let mut text = Self {
+            id: id.to_string(),
+            content: TextContent::default(),
+        };
+        text.update();
+        text
+    }
+
+    pub fn update(&mut self) {
+        let Ok(contents) = CONTENTS.read() else {
+            return;
+        };
+        self.content = contents
+            .get(&self.id)
+            .filter(|content| {
+                content
+                    .expires
+                    .is_none_or(|expires| expires > Instant::now())
+            })
+            .cloned()
+            .unwrap_or_default();
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("text", self.content.text.clone()),
+            ("color", self.content.color.clone().unwrap_or_default()),
+            ("tooltip", self.content.tooltip.clone().unwrap_or_default()),
+        ]
+    }
+
+    pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
+        if self.content.text.is_empty() {
+            return Vec::new();
+        }
+
+        let span = Span::raw(&self.content.text);
+        match self.content.color.as_deref().and_then(parse_color) {
+            Some(color) if colorize => vec![span.fg(color)],
+            _ => vec![span],
+        }
+    }
+}
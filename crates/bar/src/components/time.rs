@@ -1,5 +1,6 @@
 use chrono::{Local, Timelike};
 use ratatui::{prelude::Stylize, style::Color, text::Span};
+use std::fmt::Write;
 
 #[derive(Debug, Default, Clone)]
 pub struct Time {
@@ -21,6 +22,19 @@ impl Time {
         self.cached_span_content = self.time_string.clone();
     }
 
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![("time", self.time_string.clone())]
+    }
+
+    /// Format the current time with a strftime-style string, e.g. `"%H:%M"`
+    pub fn format(format: &str) -> String {
+        let mut formatted = String::new();
+        match write!(formatted, "{}", Local::now().format(format)) {
+            Ok(()) => formatted,
+            Err(_) => format!("invalid format: {}", format),
+        }
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let span = Span::raw(&self.cached_span_content);
         if colorize {
@@ -1,13 +1,19 @@
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use std::process::Command;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
+use crate::icons;
 use crate::logging;
 
 #[derive(Debug)]
 pub struct Volume {
     pub level: String,
     pub is_muted: bool,
+    /// Name of the default sink, refreshed in the background less often than the level
+    sink: Arc<Mutex<String>>,
     cached_span_content: String,
+    _update_handle: tokio::task::JoinHandle<()>,
 }
 
 impl Default for Volume {
@@ -20,13 +26,31 @@ impl Volume {
     pub fn new() -> Self {
         let (level, is_muted) = get_system_volume().unwrap_or((0, false));
         let level_str = level.to_string();
-        let icon = if is_muted { "󰝟" } else { "󰕾" };
-        let cached_span_content = format!("{} {}%", icon, level_str);
+        let cached_span_content = format!("{} {}%", Self::icon(is_muted), level_str);
+
+        let sink = Arc::new(Mutex::new(String::new()));
+        let sink_clone = sink.clone();
+
+        // `wpctl inspect` is slow, so look the sink up off the render path
+        let update_handle = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(5));
+
+            loop {
+                interval.tick().await;
+
+                let default_sink = get_default_sink().await.unwrap_or_default();
+                if let Ok(mut sink_guard) = sink_clone.lock() {
+                    *sink_guard = default_sink;
+                }
+            }
+        });
 
         Self {
             level: level_str,
             is_muted,
+            sink,
             cached_span_content,
+            _update_handle: update_handle,
         }
     }
 
@@ -35,8 +59,30 @@ impl Volume {
         self.level = level.to_string();
         self.is_muted = is_muted;
 
-        let icon = if self.is_muted { "󰝟" } else { "󰕾" };
-        self.cached_span_content = format!("{} {}%", icon, self.level);
+        self.cached_span_content = format!("{} {}%", Self::icon(self.is_muted), self.level);
+    }
+
+    fn icon(is_muted: bool) -> String {
+        if is_muted {
+            icons::icon("volume.muted")
+        } else {
+            icons::icon("volume")
+        }
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", Self::icon(self.is_muted)),
+            ("percent", self.level.clone()),
+            ("muted", self.is_muted.to_string()),
+            (
+                "sink",
+                self.sink
+                    .lock()
+                    .map(|sink| sink.clone())
+                    .unwrap_or_default(),
+            ),
+        ]
     }
 
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
@@ -48,18 +94,31 @@ impl Volume {
     }
 }
 
+impl Drop for Volume {
+    fn drop(&mut self) {
+        // Stop looking up the sink when the component is replaced on reload
+        self._update_handle.abort();
+    }
+}
+
 fn get_system_volume() -> Option<(i32, bool)> {
-    let output = Command::new("wpctl")
+    let output = match Command::new("wpctl")
         .args(["get-volume", "@DEFAULT_AUDIO_SINK@"])
         .output()
-        .expect("failed to get volume");
+    {
+        Ok(output) => output,
+        Err(e) => {
+            logging::log_component_error("VOLUME", &format!("Failed to run wpctl: {}", e));
+            return None;
+        }
+    };
 
     if output.status.success() {
-        let stdout = str::from_utf8(&output.stdout).unwrap();
+        let stdout = String::from_utf8_lossy(&output.stdout);
         let is_muted = stdout.contains("[MUTED]");
         let parts: Vec<&str> = stdout.split_whitespace().collect();
 
-        if let Ok(volume) = parts[1].parse::<f32>() {
+        if let Some(Ok(volume)) = parts.get(1).map(|volume| volume.parse::<f32>()) {
             return Some(((volume * 100.0) as i32, is_muted));
         }
 
@@ -76,3 +135,22 @@ fn get_system_volume() -> Option<(i32, bool)> {
 
     Some((0, false))
 }
+
+/// The `node.name` of the default audio sink, if there is one
+async fn get_default_sink() -> Option<String> {
+    let output = tokio::process::Command::new("wpctl")
+        .args(["inspect", "@DEFAULT_AUDIO_SINK@"])
+        .output()
+        .await
+        .ok()?;
+
+    if !output.status.success() {
+        return None;
+    }
+
+    let stdout = str::from_utf8(&output.stdout).ok()?;
+    stdout.lines().find_map(|line| {
+        let (key, value) = line.trim_start_matches([' ', '*']).split_once(" = ")?;
+        (key.trim() == "node.name").then(|| value.trim().trim_matches('"').to_string())
+    })
+}
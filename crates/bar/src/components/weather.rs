@@ -1,5 +1,7 @@
+use crate::icons;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use serde::Deserialize;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::time::{SystemTime, UNIX_EPOCH};
 
@@ -15,6 +17,8 @@ pub struct Weather {
     data: Arc<Mutex<WeatherData>>,
     cached_span_content: Arc<Mutex<String>>,
     last_update: Arc<Mutex<u64>>,
+    /// Whether the first fetch has finished, whether or not it succeeded
+    fetched: Arc<AtomicBool>,
     _update_handle: tokio::task::JoinHandle<()>,
 }
 
@@ -45,14 +49,19 @@ impl Weather {
         let data = Arc::new(Mutex::new(WeatherData {
             temperature: "--".to_string(),
             condition: "Unknown".to_string(),
-            icon: "󰖐".to_string(),
+            icon: icons::icon("weather.unknown"),
         }));
-        let cached_span_content = Arc::new(Mutex::new("󰖐 --°C".to_string()));
+        let cached_span_content = Arc::new(Mutex::new(format!(
+            "{} --°C",
+            icons::icon("weather.unknown")
+        )));
         let last_update = Arc::new(Mutex::new(0u64));
 
         let data_clone = data.clone();
         let cached_span_content_clone = cached_span_content.clone();
         let last_update_clone = last_update.clone();
+        let fetched = Arc::new(AtomicBool::new(false));
+        let fetched_clone = fetched.clone();
 
         // Spawn background task for weather updates
         let update_handle = tokio::spawn(async move {
@@ -84,6 +93,7 @@ impl Weather {
                         *last_update_guard = now;
                     }
                 }
+                fetched_clone.store(true, Ordering::Relaxed);
             }
         });
 
@@ -91,10 +101,15 @@ impl Weather {
             data,
             cached_span_content,
             last_update,
+            fetched,
             _update_handle: update_handle,
         }
     }
 
+    pub fn is_ready(&self) -> bool {
+        self.fetched.load(Ordering::Relaxed)
+    }
+
     pub fn update(&mut self) {
         // This is now non-blocking - data is updated in background
         // Just check if we need to trigger initial update
@@ -146,11 +161,20 @@ impl Weather {
             .clone()
     }
 
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        let data = self.get_weather_data();
+        vec![
+            ("icon", data.icon),
+            ("temp", data.temperature),
+            ("condition", data.condition),
+        ]
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let cached_content = if let Ok(guard) = self.cached_span_content.lock() {
             guard.clone()
         } else {
-            "󰖐 --°C".to_string()
+            format!("{} --°C", icons::icon("weather.unknown"))
         };
 
         let span = Span::raw(cached_content);
@@ -215,15 +239,16 @@ impl Weather {
 
     fn get_weather_icon(condition: &str) -> String {
         let condition_lower = condition.to_lowercase();
-        match condition_lower.as_str() {
-            cond if cond.contains("clear") || cond.contains("sunny") => "󰖙".to_string(),
-            cond if cond.contains("cloud") || cond.contains("overcast") => "󰖐".to_string(),
-            cond if cond.contains("rain") || cond.contains("drizzle") => "󰖗".to_string(),
-            cond if cond.contains("snow") || cond.contains("sleet") => "󰖘".to_string(),
-            cond if cond.contains("thunder") || cond.contains("storm") => "󰖓".to_string(),
-            cond if cond.contains("fog") || cond.contains("mist") => "󰖑".to_string(),
-            cond if cond.contains("wind") => "󰖝".to_string(),
-            _ => "󰖐".to_string(),
-        }
+        let key = match condition_lower.as_str() {
+            cond if cond.contains("clear") || cond.contains("sunny") => "weather.clear",
+            cond if cond.contains("cloud") || cond.contains("overcast") => "weather.cloudy",
+            cond if cond.contains("rain") || cond.contains("drizzle") => "weather.rain",
+            cond if cond.contains("snow") || cond.contains("sleet") => "weather.snow",
+            cond if cond.contains("thunder") || cond.contains("storm") => "weather.storm",
+            cond if cond.contains("fog") || cond.contains("mist") => "weather.fog",
+            cond if cond.contains("wind") => "weather.wind",
+            _ => "weather.unknown",
+        };
+        icons::icon(key)
     }
 }
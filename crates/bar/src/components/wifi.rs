@@ -1,4 +1,5 @@
 use super::sparkline::Sparkline;
+use crate::icons;
 use ratatui::{prelude::Stylize, style::Color, text::Span};
 use std::process::Command;
 use std::time::{Duration, Instant};
@@ -7,6 +8,7 @@ use std::time::{Duration, Instant};
 pub struct Wifi {
     pub status: String,
     pub network: String,
+    pub ethernet: bool,
     cached_span_content: String,
     last_update: Instant,
     update_interval: Duration,
@@ -21,14 +23,10 @@ impl Wifi {
         sparkline_update_freq: u64,
         sparkline_logarithmic: bool,
     ) -> Self {
-        let (status, network) =
-            get_wifi_status().unwrap_or(("disconnected".to_string(), "".to_string()));
+        let (status, network, ethernet) =
+            get_wifi_status().unwrap_or(("disconnected".to_string(), "".to_string(), false));
 
-        let icon = if status == "connected" {
-            "󰤨"
-        } else {
-            "󰤮"
-        };
+        let icon = Self::icon(&status);
 
         let network_text = if status == "connected" && !network.is_empty() {
             &network
@@ -46,6 +44,7 @@ impl Wifi {
         Self {
             status,
             network,
+            ethernet,
             cached_span_content,
             last_update: Instant::now(),
             update_interval: Duration::from_secs(sparkline_update_freq),
@@ -57,15 +56,12 @@ impl Wifi {
     pub fn update(&mut self) {
         let now = Instant::now();
         if now.duration_since(self.last_update) >= self.update_interval {
-            if let Some((status, network)) = get_wifi_status() {
+            if let Some((status, network, ethernet)) = get_wifi_status() {
                 self.status = status;
                 self.network = network;
+                self.ethernet = ethernet;
 
-                let icon = if self.status == "connected" {
-                    "󰤨"
-                } else {
-                    "󰤮"
-                };
+                let icon = Self::icon(&self.status);
 
                 if self.sparkline.enabled {
                     if let Some(current_bytes) = get_network_usage() {
@@ -100,6 +96,24 @@ impl Wifi {
         }
     }
 
+    fn icon(status: &str) -> String {
+        if status == "connected" {
+            icons::icon("wifi.connected")
+        } else {
+            icons::icon("wifi.disconnected")
+        }
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("icon", Self::icon(&self.status)),
+            ("ssid", self.network.clone()),
+            ("status", self.status.clone()),
+            ("ethernet", self.ethernet.to_string()),
+            ("sparkline", self.sparkline.render_with_spaces()),
+        ]
+    }
+
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
         let span = Span::raw(&self.cached_span_content);
         if colorize {
@@ -115,7 +129,8 @@ impl Wifi {
     }
 }
 
-fn get_wifi_status() -> Option<(String, String)> {
+/// Wi-Fi status and network name, plus whether a wired connection is up
+fn get_wifi_status() -> Option<(String, String, bool)> {
     let output = Command::new("nmcli")
         .args(["-t", "-f", "TYPE,STATE,CONNECTION", "device"])
         .output()
@@ -127,23 +142,28 @@ fn get_wifi_status() -> Option<(String, String)> {
 
     let stdout = str::from_utf8(&output.stdout).ok()?;
 
+    let mut status = ("disconnected".to_string(), "".to_string());
+    let mut found_wifi = false;
+    let mut ethernet = false;
+
     for line in stdout.lines() {
-        if line.starts_with("wifi:") {
-            let parts: Vec<&str> = line.split(':').collect();
-            if parts.len() >= 3 {
-                let state = parts[1].to_lowercase();
-                let connection = parts[2].to_string();
-
-                if state == "connected" {
-                    return Some(("connected".to_string(), connection));
-                } else {
-                    return Some(("disconnected".to_string(), "".to_string()));
-                }
+        let parts: Vec<&str> = line.split(':').collect();
+        if parts.len() < 3 {
+            continue;
+        }
+        let state = parts[1].to_lowercase();
+
+        if parts[0] == "ethernet" && state == "connected" {
+            ethernet = true;
+        } else if parts[0] == "wifi" && !found_wifi {
+            found_wifi = true;
+            if state == "connected" {
+                status = ("connected".to_string(), parts[2].to_string());
             }
         }
     }
 
-    Some(("disconnected".to_string(), "".to_string()))
+    Some((status.0, status.1, ethernet))
 }
 
 fn get_network_usage() -> Option<u64> {
@@ -1,8 +1,18 @@
-use ratatui::{prelude::Stylize, style::Color, text::Span};
+use ratatui::{
+    prelude::Stylize,
+    style::{Color, Modifier, Style},
+    text::Span,
+};
 use serde::Deserialize;
 use std::process::Command;
 
+use crate::apps;
+use crate::config::{WindowDisplay, WindowScope};
+use crate::icons;
 use crate::logging;
+use crate::outputs;
+use crate::overflow::truncate_spans;
+use crate::urgency;
 
 #[derive(Deserialize, Debug)]
 struct Window {
@@ -10,6 +20,15 @@ struct Window {
     class: String,
     title: String,
     workspace: Workspace,
+    #[serde(default)]
+    monitor: i32,
+    /// A bool on older Hyprland, a fullscreen mode number on newer releases
+    #[serde(default)]
+    fullscreen: serde_json::Value,
+    #[serde(default)]
+    floating: bool,
+    #[serde(default)]
+    pinned: bool,
 }
 
 #[derive(Deserialize, Debug)]
@@ -28,62 +47,187 @@ pub struct WindowInfo {
     icon: String,
     class: String,
     title: String,
+    workspace: i32,
+    fullscreen: bool,
+    floating: bool,
+    pinned: bool,
+    urgent: bool,
+}
+
+impl WindowInfo {
+    /// Glyphs for the window's fullscreen, floating, pinned and urgent flags
+    fn indicators(&self) -> String {
+        [
+            (self.fullscreen, "window.fullscreen"),
+            (self.floating, "window.floating"),
+            (self.pinned, "window.pinned"),
+            (self.urgent, "window.urgent"),
+        ]
+        .into_iter()
+        .filter(|(set, _)| *set)
+        .map(|(_, key)| format!(" {}", icons::icon(key)))
+        .collect()
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct Windows {
     pub windows: Vec<WindowInfo>,
     active_window: String,
+    display: WindowDisplay,
+    title_length: usize,
+    scope: WindowScope,
+    group_by_workspace: bool,
+    /// Output the bar runs on, followed by scoped lists
+    output: Option<String>,
+    /// Style patched over urgent windows
+    pub urgent_style: Style,
 }
 
 impl Windows {
     pub fn new() -> Self {
-        let (windows, active_window) = get_windows().unwrap_or_default();
+        Self::with_config(
+            WindowDisplay::default(),
+            24,
+            WindowScope::default(),
+            false,
+            None,
+        )
+    }
+
+    pub fn with_config(
+        display: WindowDisplay,
+        title_length: usize,
+        scope: WindowScope,
+        group_by_workspace: bool,
+        output: Option<String>,
+    ) -> Self {
+        urgency::watch();
+        let (windows, active_window) =
+            get_windows(scope, group_by_workspace, output.as_deref()).unwrap_or_default();
         Self {
             windows,
             active_window,
+            display,
+            title_length,
+            scope,
+            group_by_workspace,
+            output,
+            urgent_style: Style::default(),
         }
     }
 
     pub fn update(&mut self) {
-        let (windows, active_window) = get_windows().unwrap_or_default();
+        let (windows, active_window) =
+            get_windows(self.scope, self.group_by_workspace, self.output.as_deref())
+                .unwrap_or_default();
         self.windows = windows;
         self.active_window = active_window;
     }
 
-    pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
-        self.windows
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        let active = self
+            .windows
             .iter()
-            .map(|w| {
-                if w.address == self.active_window {
-                    if colorize {
-                        // Focused window: brand color background with appropriate text color
-                        let (bg_color, fg_color) = get_brand_color(&w.class, &w.title);
-                        Span::raw(format!(" {} ", w.icon)).bg(bg_color).fg(fg_color)
-                    } else {
-                        // Non-colorized mode: black text on white background for active window
-                        Span::raw(format!(" {} ", w.icon))
-                            .bg(Color::White)
-                            .fg(Color::Black)
-                    }
-                } else if colorize {
-                    // Unfocused window: white text on default background
-                    Span::raw(format!(" {} ", w.icon)).fg(Color::White)
+            .find(|w| w.address == self.active_window);
+        vec![
+            ("count", self.windows.len().to_string()),
+            ("class", active.map(|w| w.class.clone()).unwrap_or_default()),
+            ("title", active.map(|w| w.title.clone()).unwrap_or_default()),
+        ]
+    }
+
+    /// Text shown for one window under the configured display mode
+    fn label(&self, window: &WindowInfo) -> String {
+        let focused = window.address == self.active_window;
+        let label = match self.display {
+            WindowDisplay::Icons => window.icon.clone(),
+            WindowDisplay::FocusedTitle if !focused => window.icon.clone(),
+            WindowDisplay::FocusedTitle | WindowDisplay::Titles => {
+                format!("{} {}", window.icon, self.truncate(&window.title))
+            }
+            WindowDisplay::Title => self.truncate(&window.title),
+        };
+        format!(" {}{} ", label, window.indicators())
+    }
+
+    fn truncate(&self, title: &str) -> String {
+        let mut spans = vec![Span::raw(title)];
+        truncate_spans(&mut spans, self.title_length);
+        spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
+        if self.display == WindowDisplay::Title {
+            let Some(focused) = self
+                .windows
+                .iter()
+                .find(|w| w.address == self.active_window)
+            else {
+                return Vec::new();
+            };
+            let span = Span::raw(self.label(focused));
+            return if colorize {
+                vec![span.fg(Color::White)]
+            } else {
+                vec![span]
+            };
+        }
+
+        let mut spans = Vec::new();
+        let mut current_workspace = None;
+        for w in &self.windows {
+            if self.group_by_workspace && current_workspace != Some(w.workspace) {
+                current_workspace = Some(w.workspace);
+                let heading = Span::raw(format!(" {}:", w.workspace));
+                spans.push(if colorize {
+                    heading.fg(Color::DarkGray)
+                } else {
+                    heading
+                });
+            }
+
+            let label = self.label(w);
+            spans.push(if w.urgent && w.address != self.active_window {
+                if colorize {
+                    Span::raw(label).style(self.urgent_style)
+                } else {
+                    Span::raw(label).add_modifier(Modifier::REVERSED)
+                }
+            } else if w.address == self.active_window {
+                if colorize {
+                    // Focused window: brand color background with appropriate text color
+                    let (bg_color, fg_color) = get_brand_color(&w.class, &w.title);
+                    Span::raw(label).bg(bg_color).fg(fg_color)
                 } else {
-                    // Non-colorized mode: white text for non-active windows
-                    Span::raw(format!(" {} ", w.icon)).fg(Color::White)
+                    // Non-colorized mode: black text on white background for active window
+                    Span::raw(label).bg(Color::White).fg(Color::Black)
                 }
-            })
-            .collect::<Vec<Span>>()
+            } else if colorize {
+                // Unfocused window: white text on default background
+                Span::raw(label).fg(Color::White)
+            } else {
+                // Non-colorized mode: white text for non-active windows
+                Span::raw(label).fg(Color::White)
+            });
+        }
+        spans
     }
 }
 
-fn get_windows() -> Option<(Vec<WindowInfo>, String)> {
+fn get_windows(
+    scope: WindowScope,
+    group_by_workspace: bool,
+    output: Option<&str>,
+) -> Option<(Vec<WindowInfo>, String)> {
     // Get all windows
-    let clients_output = Command::new("hyprctl")
-        .args(["clients", "-j"])
-        .output()
-        .expect("failed to get clients");
+    let clients_output = match Command::new("hyprctl").args(["clients", "-j"]).output() {
+        Ok(output) => output,
+        Err(e) => {
+            logging::log_component_error("WINDOWS", &format!("Failed to run hyprctl: {}", e));
+            return None;
+        }
+    };
 
     if !clients_output.status.success() {
         logging::log_component_error(
@@ -93,166 +237,197 @@ fn get_windows() -> Option<(Vec<WindowInfo>, String)> {
         return None;
     }
 
-    let clients_stdout = str::from_utf8(&clients_output.stdout).unwrap();
-    let windows: Vec<Window> =
-        serde_json::from_str(clients_stdout).expect("failed to parse windows");
+    let windows: Vec<Window> = serde_json::from_slice(&clients_output.stdout)
+        .inspect_err(|e| {
+            logging::log_component_error("WINDOWS", &format!("Failed to parse windows: {}", e))
+        })
+        .ok()?;
 
     // Get active window
-    let active_output = Command::new("hyprctl")
+    let active_address = Command::new("hyprctl")
         .args(["activewindow", "-j"])
         .output()
-        .expect("failed to get active window");
-
-    let active_address = if active_output.status.success() {
-        let active_stdout = str::from_utf8(&active_output.stdout).unwrap();
-        let active_window: ActiveWindow =
-            serde_json::from_str(active_stdout).unwrap_or(ActiveWindow {
-                address: String::new(),
-            });
-        active_window.address
-    } else {
-        String::new()
+        .ok()
+        .filter(|output| output.status.success())
+        .and_then(|output| serde_json::from_slice::<ActiveWindow>(&output.stdout).ok())
+        .map(|active_window| active_window.address)
+        .unwrap_or_default();
+
+    let monitor = match scope {
+        WindowScope::All => None,
+        WindowScope::Workspace | WindowScope::Monitor => Some(outputs::bar_monitor(output)?),
     };
 
-    let window_infos = windows
+    let mut window_infos: Vec<WindowInfo> = windows
         .iter()
         .filter(|w| w.workspace.id > 0) // Filter out special workspaces
+        .filter(|w| match (&monitor, scope) {
+            (Some(monitor), WindowScope::Workspace) => {
+                w.workspace.id == monitor.active_workspace.id
+            }
+            (Some(monitor), WindowScope::Monitor) => w.monitor == monitor.id,
+            _ => true,
+        })
         .map(|w| WindowInfo {
             address: w.address.clone(),
             icon: get_app_icon(&w.class, &w.title),
             class: w.class.clone(),
             title: w.title.clone(),
+            workspace: w.workspace.id,
+            fullscreen: match &w.fullscreen {
+                serde_json::Value::Bool(fullscreen) => *fullscreen,
+                serde_json::Value::Number(mode) => mode.as_i64().unwrap_or(0) > 0,
+                _ => false,
+            },
+            floating: w.floating,
+            pinned: w.pinned,
+            // `hyprctl clients` doesn't report urgency, so it comes from the event socket
+            urgent: urgency::is_window_urgent(&w.address),
         })
         .collect();
 
+    if group_by_workspace {
+        window_infos.sort_by_key(|w| w.workspace);
+    }
+
     Some((window_infos, active_address))
 }
 
 fn get_app_icon(class: &str, title: &str) -> String {
+    if let Some(icon) = apps::lookup(class, title).and_then(|style| style.icon) {
+        return icon;
+    }
+
+    let (glyph, category) = app_glyph(class, title);
+    icons::app_icon(category, glyph)
+}
+
+/// Nerd Font glyph and icon category for an application
+fn app_glyph(class: &str, title: &str) -> (&'static str, &'static str) {
     // First check title for terminal applications with specific commands
     let title_lower = title.to_lowercase();
     if title_lower.starts_with("nvim") || title_lower.contains("neovim") {
-        return "".to_string();
+        return ("", "editor");
     } else if title_lower.starts_with("vim") {
-        return "".to_string();
+        return ("", "editor");
     } else if title_lower.starts_with("emacs") {
-        return "󰍹".to_string();
+        return ("󰍹", "editor");
     } else if title_lower.starts_with("nano") {
-        return "".to_string();
+        return ("", "editor");
     } else if title_lower.starts_with("htop") || title_lower.starts_with("btop") {
-        return "󰔚".to_string();
+        return ("󰔚", "system");
     } else if title_lower.starts_with("yazi") {
-        return "󰇥".to_string();
+        return ("󰇥", "files");
     } else if title_lower.starts_with("ranger") || title_lower.starts_with("lf") {
-        return "󰉋".to_string();
+        return ("󰉋", "files");
     } else if title_lower.starts_with("git") {
-        return "󰊢".to_string();
+        return ("󰊢", "development");
     } else if title_lower.starts_with("man") {
-        return "󰍹".to_string();
+        return ("󰍹", "document");
     } else if title_lower.starts_with("ssh") {
-        return "󰣀".to_string();
+        return ("󰣀", "terminal");
     } else if title_lower.starts_with("cmus") || title_lower.starts_with("ncmpcpp") {
-        return "󰓇".to_string();
+        return ("󰓇", "music");
     }
 
     // Fall back to class-based detection
     match class.to_lowercase().as_str() {
         // Browsers
-        "firefox" | "firefox-developer-edition" => "󰈹".to_string(),
-        "google-chrome" | "chrome" => "󰊯".to_string(),
-        "chromium" => "󰊯".to_string(),
-        "brave-browser" => "󰖟".to_string(),
-        "librewolf" => "󰈹".to_string(),
-        "vivaldi" => "󰖟".to_string(),
-        "opera" => "󰖟".to_string(),
-        "edge" => "󰇩".to_string(),
-        "helium" => "󰖟".to_string(),
+        "firefox" | "firefox-developer-edition" => ("󰈹", "browser"),
+        "google-chrome" | "chrome" => ("󰊯", "browser"),
+        "chromium" => ("󰊯", "browser"),
+        "brave-browser" => ("󰖟", "browser"),
+        "librewolf" => ("󰈹", "browser"),
+        "vivaldi" => ("󰖟", "browser"),
+        "opera" => ("󰖟", "browser"),
+        "edge" => ("󰇩", "browser"),
+        "helium" => ("󰖟", "browser"),
 
         // Terminal Emulators (fallback when title doesn't match specific commands)
-        "kitty" => "󰄛".to_string(),
-        "alacritty" => "󰆍".to_string(),
-        "gnome-terminal" => "󰆍".to_string(),
-        "konsole" => "󰆍".to_string(),
-        "xterm" => "󰆍".to_string(),
+        "kitty" => ("󰄛", "terminal"),
+        "alacritty" => ("󰆍", "terminal"),
+        "gnome-terminal" => ("󰆍", "terminal"),
+        "konsole" => ("󰆍", "terminal"),
+        "xterm" => ("󰆍", "terminal"),
 
         // Standalone Editor Applications (GUI-based)
-        "neovide" => "".to_string(),
-        "code" | "code-oss" => "󰨞".to_string(),
-        "sublime_text" => "󰅪".to_string(),
+        "neovide" => ("", "editor"),
+        "code" | "code-oss" => ("󰨞", "editor"),
+        "sublime_text" => ("󰅪", "editor"),
 
         // PDF & Document Viewers
-        "zathura" => "󰈦".to_string(),
-        "evince" => "󰈦".to_string(),
-        "okular" => "󰈦".to_string(),
-        "qpdfview" => "󰈦".to_string(),
-        "mupdf" => "󰈦".to_string(),
+        "zathura" => ("󰈦", "document"),
+        "evince" => ("󰈦", "document"),
+        "okular" => ("󰈦", "document"),
+        "qpdfview" => ("󰈦", "document"),
+        "mupdf" => ("󰈦", "document"),
 
         // Image Viewers
-        "qview" => "󰋩".to_string(),
-        "feh" => "󰋩".to_string(),
-        "nomacs" => "󰋩".to_string(),
-        "gwenview" => "󰋩".to_string(),
-        "eog" => "󰋩".to_string(),
-        "sxiv" => "󰋩".to_string(),
+        "qview" => ("󰋩", "image"),
+        "feh" => ("󰋩", "image"),
+        "nomacs" => ("󰋩", "image"),
+        "gwenview" => ("󰋩", "image"),
+        "eog" => ("󰋩", "image"),
+        "sxiv" => ("󰋩", "image"),
 
         // Video Players
-        "mpv" => "󰐹".to_string(),
-        "vlc" => "󰕼".to_string(),
-        "smplayer" => "󰐹".to_string(),
-        "celluloid" => "󰐹".to_string(),
+        "mpv" => ("󰐹", "video"),
+        "vlc" => ("󰕼", "video"),
+        "smplayer" => ("󰐹", "video"),
+        "celluloid" => ("󰐹", "video"),
 
         // Music & Audio
-        "spotify" => "󰓇".to_string(),
-        "rhythmbox" => "󰓇".to_string(),
-        "audacious" => "󰓇".to_string(),
-        "cmus" => "󰓇".to_string(),
-        "ncmpcpp" => "󰓇".to_string(),
+        "spotify" => ("󰓇", "music"),
+        "rhythmbox" => ("󰓇", "music"),
+        "audacious" => ("󰓇", "music"),
+        "cmus" => ("󰓇", "music"),
+        "ncmpcpp" => ("󰓇", "music"),
 
         // Graphics & Design
-        "gimp" => "󰏘".to_string(),
-        "aseprite" => "󰆧".to_string(),
-        "inkscape" => "󰝫".to_string(),
-        "blender" => "󰂫".to_string(),
-        "krita" => "󰏘".to_string(),
-        "obs" => "󰕀".to_string(),
+        "gimp" => ("󰏘", "graphics"),
+        "aseprite" => ("󰆧", "graphics"),
+        "inkscape" => ("󰝫", "graphics"),
+        "blender" => ("󰂫", "graphics"),
+        "krita" => ("󰏘", "graphics"),
+        "obs" => ("󰕀", "graphics"),
 
         // Communication
-        "discord" => "󰙯".to_string(),
-        "telegramdesktop" | "telegram" => "󰈨".to_string(),
-        "slack" => "󰒱".to_string(),
-        "signal" => "󰍦".to_string(),
-        "thunderbird" => "󰇰".to_string(),
-        "geary" => "󰇰".to_string(),
+        "discord" => ("󰙯", "chat"),
+        "telegramdesktop" | "telegram" => ("󰈨", "chat"),
+        "slack" => ("󰒱", "chat"),
+        "signal" => ("󰍦", "chat"),
+        "thunderbird" => ("󰇰", "mail"),
+        "geary" => ("󰇰", "mail"),
 
         // File Managers (GUI-based)
-        "thunar" => "󰉋".to_string(),
-        "dolphin" => "󰉋".to_string(),
-        "nautilus" => "󰉋".to_string(),
-        "pcmanfm" => "󰉋".to_string(),
+        "thunar" => ("󰉋", "files"),
+        "dolphin" => ("󰉋", "files"),
+        "nautilus" => ("󰉋", "files"),
+        "pcmanfm" => ("󰉋", "files"),
 
         // System Tools (GUI-based)
-        "nvtop" => "󰍛".to_string(),
-        "pavucontrol" => "󰝚".to_string(),
-        "networkmanager_dmenu" => "󰤨".to_string(),
+        "nvtop" => ("󰍛", "system"),
+        "pavucontrol" => ("󰝚", "system"),
+        "networkmanager_dmenu" => ("󰤨", "system"),
 
         // Office & Productivity
-        "libreoffice-writer" => "󰏪".to_string(),
-        "libreoffice-calc" => "󰈛".to_string(),
-        "libreoffice-impress" => "󰎧".to_string(),
-        "onlyoffice-desktopeditors" => "󰏪".to_string(),
+        "libreoffice-writer" => ("󰏪", "office"),
+        "libreoffice-calc" => ("󰈛", "office"),
+        "libreoffice-impress" => ("󰎧", "office"),
+        "onlyoffice-desktopeditors" => ("󰏪", "office"),
 
         // Development Tools
-        "postman" => "󰮮".to_string(),
-        "insomnia" => "󰘦".to_string(),
-        "gitkraken" => "󰊢".to_string(),
-        "figma-linux" => "󰿭".to_string(),
-        "wine" | "winecfg" => "󰡶".to_string(),
+        "postman" => ("󰮮", "development"),
+        "insomnia" => ("󰘦", "development"),
+        "gitkraken" => ("󰊢", "development"),
+        "figma-linux" => ("󰿭", "development"),
+        "wine" | "winecfg" => ("󰡶", "development"),
 
         // Games
-        "steam" => "󰓓".to_string(),
-        "lutris" => "󰮭".to_string(),
-        "heroic" => "󰔑".to_string(),
-        "minecraft" => "󰍳".to_string(),
+        "steam" => ("󰓓", "game"),
+        "lutris" => ("󰮭", "game"),
+        "heroic" => ("󰔑", "game"),
+        "minecraft" => ("󰍳", "game"),
 
         // Generic fallbacks
         // TODO: I've commented these out because there is no way of determining the nature of an
@@ -266,11 +441,23 @@ fn get_app_icon(class: &str, title: &str) -> String {
         // "image_viewer" => "󰋩".to_string(),
 
         // Default fallback
-        _ => "󰍜".to_string(),
+        _ => ("󰍜", "window"),
     }
 }
 
+/// Focused window (background, text) colors, from the `apps` config over the built-in brands
 fn get_brand_color(class: &str, title: &str) -> (Color, Color) {
+    let (default_bg, default_fg) = default_brand_color(class, title);
+    match apps::lookup(class, title) {
+        Some(style) => (
+            style.bg.unwrap_or(default_bg),
+            style.fg.unwrap_or(default_fg),
+        ),
+        None => (default_bg, default_fg),
+    }
+}
+
+fn default_brand_color(class: &str, title: &str) -> (Color, Color) {
     // First check title for terminal applications with specific commands
     let title_lower = title.to_lowercase();
     let class_lower = class.to_lowercase();
@@ -1,31 +1,184 @@
-use ratatui::{prelude::Stylize, style::Color, text::Span};
+use ratatui::{
+    prelude::Stylize,
+    style::{Color, Modifier, Style},
+    text::Span,
+};
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::process::Command;
 
+use crate::config::WindowScope;
+use crate::icons;
 use crate::logging;
+use crate::outputs;
+use crate::urgency;
 
 #[derive(Deserialize, Debug)]
 struct Workspace {
     id: i32,
+    #[serde(default)]
+    name: String,
+    #[serde(default)]
+    windows: u32,
+    #[serde(default)]
+    monitor: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct WorkspaceInfo {
+    pub id: i32,
+    pub name: String,
+    pub windows: u32,
+    pub monitor: String,
+}
+
+impl WorkspaceInfo {
+    /// Special (scratchpad) workspaces have negative ids and a `special:` name
+    fn is_special(&self) -> bool {
+        self.name.starts_with("special")
+    }
 }
 
 #[derive(Debug, Default, Clone)]
 pub struct Workspaces {
-    pub workspaces: Vec<String>,
-    pub active_workspace: String,
+    pub workspaces: Vec<WorkspaceInfo>,
+    pub active_workspace: i32,
+    persistent: Vec<String>,
+    labels: BTreeMap<String, String>,
+    show_special: bool,
+    window_count: bool,
+    scope: WindowScope,
+    /// Output the bar runs on, followed by scoped lists
+    output: Option<String>,
+    /// Style patched over workspaces holding an urgent window
+    pub urgent_style: Style,
 }
 
 impl Workspaces {
     pub fn new() -> Self {
-        Self {
-            workspaces: get_workspaces().unwrap_or_default(),
-            active_workspace: get_active_workspace().unwrap_or_default(),
-        }
+        Self::with_config(
+            Vec::new(),
+            BTreeMap::new(),
+            true,
+            false,
+            WindowScope::default(),
+            None,
+        )
+    }
+
+    pub fn with_config(
+        persistent: Vec<String>,
+        labels: BTreeMap<String, String>,
+        show_special: bool,
+        window_count: bool,
+        scope: WindowScope,
+        output: Option<String>,
+    ) -> Self {
+        let mut workspaces = Self {
+            workspaces: Vec::new(),
+            active_workspace: 0,
+            persistent,
+            labels,
+            show_special,
+            window_count,
+            scope,
+            output,
+            urgent_style: Style::default(),
+        };
+        urgency::watch();
+        workspaces.update();
+        workspaces
     }
 
     pub fn update(&mut self) {
-        self.workspaces = get_workspaces().unwrap_or_default();
-        self.active_workspace = get_active_workspace().unwrap_or_default();
+        // Without Hyprland there is nothing to list, so fall back to no workspaces
+        let mut workspaces = get_workspaces().unwrap_or_else(|e| {
+            logging::log_component_error("WORKSPACES", &format!("{}", e));
+            Vec::new()
+        });
+        if !self.show_special {
+            workspaces.retain(|w| !w.is_special());
+        }
+
+        // Scoped lists follow the bar's own monitor rather than the focused one
+        let monitor = match self.scope {
+            WindowScope::All => None,
+            WindowScope::Workspace | WindowScope::Monitor => {
+                outputs::bar_monitor(self.output.as_deref())
+            }
+        };
+        if let Some(monitor) = &monitor {
+            match self.scope {
+                WindowScope::Workspace => {
+                    workspaces.retain(|w| w.id == monitor.active_workspace.id)
+                }
+                _ => workspaces.retain(|w| w.monitor == monitor.name),
+            }
+        }
+
+        // Persistent workspaces stand in for ones that don't exist yet
+        for persistent in &self.persistent {
+            let exists = workspaces
+                .iter()
+                .any(|w| w.name == *persistent || w.id.to_string() == *persistent);
+            if !exists {
+                workspaces.push(WorkspaceInfo {
+                    id: persistent.parse().unwrap_or(i32::MAX),
+                    name: persistent.clone(),
+                    windows: 0,
+                    monitor: String::new(),
+                });
+            }
+        }
+
+        // Regular workspaces in id order, then named ones, then special ones
+        workspaces.sort_by_key(|w| (w.is_special(), w.id < 0, w.id));
+
+        self.workspaces = workspaces;
+        self.active_workspace = match monitor {
+            Some(monitor) => monitor.active_workspace.id,
+            None => get_active_workspace().unwrap_or_else(|e| {
+                logging::log_component_error("WORKSPACES", &format!("{}", e));
+                0
+            }),
+        };
+    }
+
+    /// Text shown for a workspace: its configured label, otherwise its name
+    fn label(&self, workspace: &WorkspaceInfo) -> String {
+        let label = self
+            .labels
+            .get(&workspace.name)
+            .or_else(|| self.labels.get(&workspace.id.to_string()))
+            .cloned();
+        let label = match label {
+            Some(label) => label,
+            None if workspace.is_special() => {
+                let name = workspace
+                    .name
+                    .trim_start_matches("special")
+                    .trim_start_matches(':');
+                format!("{}{}", icons::icon("workspace.special"), name)
+            }
+            None => workspace.name.clone(),
+        };
+
+        if self.window_count && workspace.windows > 0 {
+            format!("{}{}", label, superscript(workspace.windows))
+        } else {
+            label
+        }
+    }
+
+    pub fn values(&self) -> Vec<(&'static str, String)> {
+        let active = self
+            .workspaces
+            .iter()
+            .find(|w| w.id == self.active_workspace);
+        vec![
+            ("active", active.map(|w| w.name.clone()).unwrap_or_default()),
+            ("count", self.workspaces.len().to_string()),
+        ]
     }
 
     pub fn render_as_spans(&self, colorize: bool) -> Vec<Span<'_>> {
@@ -39,85 +192,94 @@ impl Workspaces {
             Color::LightRed, // 7
         ];
 
+        // Numbered workspaces take a rainbow color; named, special and placeholder ones have none
+        let rainbow_color = |w: &WorkspaceInfo| {
+            (w.id > 0 && w.id != i32::MAX)
+                .then(|| rainbow_colors[(w.id - 1) as usize % rainbow_colors.len()])
+        };
+
         self.workspaces
             .iter()
             .map(|w| {
-                if w == &self.active_workspace {
+                let span = Span::raw(format!(" {} ", self.label(w)));
+                if w.id != self.active_workspace && urgency::is_workspace_urgent(w.id) {
+                    if colorize {
+                        span.style(self.urgent_style)
+                    } else {
+                        span.add_modifier(Modifier::REVERSED)
+                    }
+                } else if w.id == self.active_workspace {
                     if colorize {
-                        if let Ok(workspace_num) = w.parse::<usize>() {
-                            let color_index = (workspace_num - 1) % rainbow_colors.len();
-                            let bg_color = rainbow_colors[color_index];
-                            // Use black text for better readability on all colored backgrounds
-                            Span::raw(format!(" {} ", w)).bg(bg_color).fg(Color::Black)
-                        } else {
-                            // Fallback for non-numeric workspace names
-                            Span::raw(format!(" {} ", w))
-                                .bg(Color::White)
-                                .fg(Color::Black)
-                        }
+                        // Use black text for better readability on all colored backgrounds
+                        let bg_color = rainbow_color(w).unwrap_or(Color::White);
+                        span.bg(bg_color).fg(Color::Black)
                     } else {
                         // Non-colorized mode: black text on white background for active workspace
-                        Span::raw(format!(" {} ", w))
-                            .bg(Color::White)
-                            .fg(Color::Black)
+                        span.bg(Color::White).fg(Color::Black)
                     }
                 } else if colorize {
-                    if let Ok(workspace_num) = w.parse::<usize>() {
-                        let color_index = (workspace_num - 1) % rainbow_colors.len();
-                        let color = rainbow_colors[color_index];
-                        Span::raw(format!(" {} ", w)).fg(color)
-                    } else {
-                        Span::raw(format!(" {} ", w))
+                    match rainbow_color(w) {
+                        Some(color) => span.fg(color),
+                        None => span,
                     }
                 } else {
                     // Non-colorized mode: white text for non-active workspaces
-                    Span::raw(format!(" {} ", w)).fg(Color::White)
+                    span.fg(Color::White)
                 }
             })
             .collect::<Vec<Span>>()
     }
 }
 
-fn get_workspaces() -> Option<Vec<String>> {
+/// Render a window count as superscript digits
+fn superscript(count: u32) -> String {
+    const DIGITS: [char; 10] = ['⁰', '¹', '²', '³', '⁴', '⁵', '⁶', '⁷', '⁸', '⁹'];
+    count
+        .to_string()
+        .chars()
+        .filter_map(|digit| digit.to_digit(10))
+        .map(|digit| DIGITS[digit as usize])
+        .collect()
+}
+
+fn get_workspaces() -> color_eyre::Result<Vec<WorkspaceInfo>> {
     let output = Command::new("hyprctl")
         .args(["workspaces", "-j"])
-        .output()
-        .expect("failed to get workspaces");
-
-    if output.status.success() {
-        let stdout = str::from_utf8(&output.stdout).unwrap();
-        let json: Vec<Workspace> =
-            serde_json::from_str(stdout).expect("failed to parse workspaces");
-
-        return Some(json.iter().map(|j| j.id.clone().to_string()).collect());
-    } else {
-        logging::log_component_error(
-            "WORKSPACES",
-            str::from_utf8(&output.stderr).unwrap_or("unknown error"),
-        );
+        .output()?;
+    if !output.status.success() {
+        return Err(color_eyre::eyre::eyre!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
 
-    None
+    let json: Vec<Workspace> = serde_json::from_slice(&output.stdout)?;
+    Ok(json
+        .into_iter()
+        .map(|j| WorkspaceInfo {
+            name: if j.name.is_empty() {
+                j.id.to_string()
+            } else {
+                j.name
+            },
+            id: j.id,
+            windows: j.windows,
+            monitor: j.monitor,
+        })
+        .collect())
 }
 
-fn get_active_workspace() -> Option<String> {
+fn get_active_workspace() -> color_eyre::Result<i32> {
     let output = Command::new("hyprctl")
         .args(["activeworkspace", "-j"])
-        .output()
-        .expect("failed to get active workspace");
-
-    if output.status.success() {
-        let stdout = str::from_utf8(&output.stdout).unwrap();
-        let json: Workspace =
-            serde_json::from_str(stdout).expect("failed to parse active workspace");
-
-        return Some(json.id.clone().to_string());
-    } else {
-        logging::log_component_error(
-            "WORKSPACES",
-            str::from_utf8(&output.stderr).unwrap_or("unknown error"),
-        );
+        .output()?;
+    if !output.status.success() {
+        return Err(color_eyre::eyre::eyre!(
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
     }
 
-    None
+    let json: Workspace = serde_json::from_slice(&output.stdout)?;
+    Ok(json.id)
 }
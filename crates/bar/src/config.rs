@@ -1,80 +1,664 @@
+use ratatui::style::{Color, Modifier, Style};
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 #[serde(untagged)]
 pub enum ComponentConfig {
     String(String),
-    Object(ComponentOptions),
+    Group(SegmentGroup),
+    Object(Box<ComponentOptions>),
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Components drawn together as one styled segment
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SegmentGroup {
+    pub segment: Vec<ComponentConfig>,
+    #[serde(flatten)]
+    pub style: StyleConfig,
+    /// Higher priority segments are hidden last when the section overflows (default 0)
+    #[serde(default)]
+    pub priority: Option<i32>,
+}
+
+/// Colors are names (`"blue"`, `"lightred"`), `"#rrggbb"` or 256-color indices
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct StyleConfig {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bold: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blink: Option<bool>,
+    /// Swap foreground and background
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reversed: Option<bool>,
+}
+
+impl StyleConfig {
+    pub fn to_style(&self) -> Style {
+        let mut style = Style::default();
+        if let Some(fg) = self.fg.as_deref().and_then(parse_color) {
+            style = style.fg(fg);
+        }
+        if let Some(bg) = self.bg.as_deref().and_then(parse_color) {
+            style = style.bg(bg);
+        }
+        if self.bold == Some(true) {
+            style = style.add_modifier(Modifier::BOLD);
+        }
+        if self.blink == Some(true) {
+            style = style.add_modifier(Modifier::SLOW_BLINK);
+        }
+        if self.reversed == Some(true) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        style
+    }
+}
+
+pub fn parse_color(color: &str) -> Option<Color> {
+    color.parse().ok()
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentOptions {
+    /// Built-in component name or the file stem of a Lua component
+    #[schemars(extend("examples" = BUILTIN_COMPONENTS))]
     pub name: String,
+    /// Name that `catfood msg set` uses to update a `text` component (default `text`)
+    #[serde(default)]
+    pub id: Option<String>,
+    /// Show a sparkline instead of the current value (cpu, ram, wifi)
     #[serde(default)]
     pub sparkline: Option<bool>,
+    /// Number of samples in the sparkline (cpu, ram, wifi)
     #[serde(default)]
     pub sparkline_length: Option<usize>,
+    /// Seconds between sparkline samples (cpu, ram, wifi)
     #[serde(default)]
     pub sparkline_update_freq: Option<u64>,
+    /// Scale the sparkline logarithmically (cpu, ram, wifi)
     #[serde(default)]
     pub sparkline_logarithmic: Option<bool>,
+    /// Higher priority components are hidden last when the section overflows (default 0)
+    #[serde(default)]
+    pub priority: Option<i32>,
+    /// Allow the component to be ellipsized down to this many cells before hiding it
+    #[serde(default)]
+    pub min_width: Option<u16>,
+    /// Always ellipsize the component beyond this many cells
+    #[serde(default)]
+    pub max_width: Option<u16>,
+    /// Text drawn by the component (separator)
+    #[serde(default)]
+    pub glyph: Option<String>,
+    /// Text template using the component's placeholders, e.g. `"{icon} {percent}%"`;
+    /// for `time` this is a strftime string such as `"%H:%M"`. Not accepted by components
+    /// that draw several labels or have no placeholders
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Template shown instead of `format` after the component is clicked
+    #[serde(default)]
+    pub format_alt: Option<String>,
+    /// Named thresholds for the component's value, e.g. `{"warning": 70, "critical": 90}`;
+    /// each state is styled by the matching `theme` entry
+    #[serde(default)]
+    pub states: Option<BTreeMap<String, f64>>,
+    /// What the `windows` component shows for each window
+    #[serde(default)]
+    pub display: Option<WindowDisplay>,
+    /// Longest window title shown by `windows`, in cells (default 24)
+    #[serde(default)]
+    pub title_length: Option<usize>,
+    /// Which windows `windows` lists, or which workspaces `workspaces` lists
+    #[serde(default)]
+    pub scope: Option<WindowScope>,
+    /// Order windows by workspace and label each workspace's group
+    #[serde(default)]
+    pub group_by_workspace: Option<bool>,
+    /// Workspaces `workspaces` always shows, by id or name, even when empty
+    #[serde(default)]
+    pub persistent: Option<Vec<String>>,
+    /// Text shown for a workspace instead of its name, keyed by id or name
+    #[serde(default)]
+    pub labels: Option<BTreeMap<String, String>>,
+    /// Show special (scratchpad) workspaces in `workspaces` (default true)
+    #[serde(default)]
+    pub show_special: Option<bool>,
+    /// Show how many windows each workspace holds
+    #[serde(default)]
+    pub window_count: Option<bool>,
+    /// Shell command run by an `exec` component
+    #[serde(default)]
+    pub command: Option<String>,
+    /// Seconds between runs of an `exec` command, or before restarting a streaming one (default 10)
+    #[serde(default)]
+    pub interval: Option<u64>,
+    /// Keep the `exec` command running and show each line it prints
+    #[serde(default)]
+    pub streaming: Option<bool>,
+    /// Parse `exec` output as JSON with `text`, `color`, `class` and `percentage` fields
+    #[serde(default)]
+    pub json: Option<bool>,
+    /// Only show the component while this rule holds, e.g. `"percent < 95"`
+    #[serde(default)]
+    pub show_when: Option<String>,
+    /// Hide the component while this rule holds, e.g. `"state == full"`
+    #[serde(default)]
+    pub hide_when: Option<String>,
+}
+
+/// Names of the components built into the bar
+pub const BUILTIN_COMPONENTS: &[&str] = &[
+    "workspaces",
+    "windows",
+    "window_title",
+    "time",
+    "weather",
+    "temperature",
+    "cpu",
+    "ram",
+    "wifi",
+    "brightness",
+    "volume",
+    "battery",
+    "separator",
+    "space",
+    "text",
+    "exec",
+];
+
+/// Options every component accepts
+const COMMON_OPTIONS: &[&str] = &[
+    "name",
+    "priority",
+    "min_width",
+    "max_width",
+    "format",
+    "format_alt",
+    "states",
+    "show_when",
+    "hide_when",
+];
+
+/// Whether a component is drawn as one piece of text that `format` and `format_alt` can replace
+///
+/// Workspaces and windows style each label on their own and separators, spaces and Lua
+/// components have no placeholders, so a template would either flatten them or do nothing.
+pub fn accepts_format(name: &str) -> bool {
+    BUILTIN_COMPONENTS.contains(&name)
+        && !matches!(
+            name,
+            "workspaces" | "windows" | "window_title" | "separator" | "space"
+        )
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Options a built-in component accepts beyond [`COMMON_OPTIONS`]
+fn component_options(name: &str) -> &'static [&'static str] {
+    match name {
+        "workspaces" => &[
+            "persistent",
+            "labels",
+            "show_special",
+            "window_count",
+            "scope",
+        ],
+        "windows" | "window_title" => &["display", "title_length", "scope", "group_by_workspace"],
+        "cpu" | "ram" | "wifi" => &[
+            "sparkline",
+            "sparkline_length",
+            "sparkline_update_freq",
+            "sparkline_logarithmic",
+        ],
+        "separator" => &["glyph"],
+        "text" => &["id"],
+        "exec" => &["command", "interval", "streaming", "json"],
+        _ => &[],
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Config {
+    /// Additional config fragments merged underneath this file, relative to its directory
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub include: Vec<String>,
+    /// Components for each section; may come entirely from `include`d fragments
+    #[serde(default)]
     pub bars: BarsConfig,
+    /// Color components according to their state (default true)
+    #[serde(default = "default_colorize")]
     pub colorize: bool,
+    /// How sections are arranged and sized
+    #[serde(default)]
+    pub layout: LayoutConfig,
+    /// How segment groups are capped and joined
+    #[serde(default)]
+    pub segments: SegmentsConfig,
+    /// Which glyphs components draw
+    #[serde(default)]
+    pub icons: IconsConfig,
+    /// Icons and colors for windows, checked in order before the built-in defaults
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub apps: Vec<AppRule>,
+    /// Styles for component states, layered over the built-in `warning`, `critical` and `urgent`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub theme: BTreeMap<String, StyleConfig>,
+    /// Named overrides, selected with `--profile` or by matching hostname
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+    /// Where the bar's panel sits and how big it is
+    #[serde(default)]
+    pub panel: PanelConfig,
+    /// Outputs to run a bar on (every monitor when empty), each with overrides for its bar
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub outputs: BTreeMap<String, OutputConfig>,
+    /// The profile that was applied when this config was loaded
+    #[serde(skip)]
+    pub active_profile: Option<String>,
+    /// The profile asked for with `--profile`, replayed on reload; hostname matches are
+    /// redone instead
+    #[serde(skip)]
+    pub requested_profile: Option<String>,
+    /// The output whose overrides were applied when this config was loaded
+    #[serde(skip)]
+    pub active_output: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ProfileConfig {
+    /// Hostnames that select this profile when none is given explicitly
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hostnames: Vec<String>,
+    /// Any top-level config keys, merged over the base config
+    #[serde(flatten)]
+    pub overrides: Map<String, Value>,
+}
+
+/// Placement of the panel the bar runs in
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PanelConfig {
+    /// What `catfood bar` starts the bar in
+    #[serde(default)]
+    pub launcher: Launcher,
+    /// Window class (app id) for the `foot` and `alacritty` launchers (default `catfood-bar`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default)]
+    pub edge: PanelEdge,
+    /// Height in lines, for panels on the top or bottom edge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<u16>,
+    /// Width in columns, for panels on the left or right edge
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub columns: Option<u16>,
+    /// Gaps between the panel and the screen edges, in pixels
+    #[serde(default)]
+    pub margins: PanelMargins,
+    /// Output to place the panel on when `outputs` isn't used
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub output: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub layer: Option<PanelLayer>,
+    /// Space reserved for the panel, in pixels; the compositor's default when unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exclusive_zone: Option<i32>,
+}
+
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema, clap::ValueEnum,
+)]
+#[serde(rename_all = "lowercase")]
+pub enum Launcher {
+    /// A `kitten panel` docked to a screen edge
+    #[default]
+    Kitten,
+    /// A foot window, for the compositor to place by its class
+    Foot,
+    /// An Alacritty window, for the compositor to place by its class
+    Alacritty,
+    /// The terminal `catfood bar` was run from
+    Current,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PanelEdge {
+    #[default]
+    Top,
+    Bottom,
+    Left,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, JsonSchema)]
+pub struct PanelMargins {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub top: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bottom: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<u16>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub right: Option<u16>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum PanelLayer {
+    Background,
+    Bottom,
+    Top,
+    Overlay,
+}
+
+impl PanelConfig {
+    /// Arguments for `kitten panel`, excluding the target output
+    pub fn kitten_args(&self) -> Vec<String> {
+        let edge = match self.edge {
+            PanelEdge::Top => "top",
+            PanelEdge::Bottom => "bottom",
+            PanelEdge::Left => "left",
+            PanelEdge::Right => "right",
+        };
+        let mut args = vec![format!("--edge={}", edge)];
+
+        if let Some(lines) = self.lines {
+            args.push(format!("--lines={}", lines));
+        }
+        if let Some(columns) = self.columns {
+            args.push(format!("--columns={}", columns));
+        }
+
+        for (side, margin) in [
+            ("top", self.margins.top),
+            ("bottom", self.margins.bottom),
+            ("left", self.margins.left),
+            ("right", self.margins.right),
+        ] {
+            if let Some(margin) = margin {
+                args.push(format!("--margin-{}={}", side, margin));
+            }
+        }
+
+        if let Some(layer) = self.layer {
+            let layer = match layer {
+                PanelLayer::Background => "background",
+                PanelLayer::Bottom => "bottom",
+                PanelLayer::Top => "top",
+                PanelLayer::Overlay => "overlay",
+            };
+            args.push(format!("--layer={}", layer));
+        }
+
+        if let Some(exclusive_zone) = self.exclusive_zone {
+            args.push(format!("--exclusive-zone={}", exclusive_zone));
+            args.push("--override-exclusive-zone".to_string());
+        }
+
+        args
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct OutputConfig {
+    /// Any top-level config keys, merged over the base config and profile for this output
+    #[serde(flatten)]
+    pub overrides: Map<String, Value>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SegmentsConfig {
+    #[serde(default)]
+    pub style: SegmentStyle,
+    /// Spaces either side of a segment's content
+    #[serde(default = "default_segment_padding")]
+    pub padding: usize,
+    /// Glyph before a segment, overriding the style's default
+    #[serde(default)]
+    pub open: Option<String>,
+    /// Glyph after a segment, overriding the style's default
+    #[serde(default)]
+    pub close: Option<String>,
+}
+
+fn default_segment_padding() -> usize {
+    1
+}
+
+impl Default for SegmentsConfig {
+    fn default() -> Self {
+        Self {
+            style: SegmentStyle::default(),
+            padding: default_segment_padding(),
+            open: None,
+            close: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SegmentStyle {
+    /// Background blocks with no glyphs
+    #[default]
+    Plain,
+    /// Powerline arrows chaining each segment into the next
+    Arrow,
+    /// Rounded caps on both ends of every segment
+    Rounded,
+    /// A pipe between neighbouring segments
+    Pipe,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "kebab-case")]
+pub enum WindowDisplay {
+    /// An icon per window
+    #[default]
+    Icons,
+    /// Icons, with the focused window's title beside its icon
+    FocusedTitle,
+    /// An icon and title per window
+    Titles,
+    /// Only the focused window's title
+    Title,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum WindowScope {
+    /// Everything on every monitor
+    #[default]
+    All,
+    /// Only the active workspace of the bar's monitor
+    Workspace,
+    /// Everything on the bar's monitor
+    Monitor,
+}
+
+/// Appearance of windows whose class and title match; patterns are case-insensitive regexes
+/// and a rule needs at least one of them
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct AppRule {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub class: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub title: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+    /// Background of the focused window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bg: Option<String>,
+    /// Text color of the focused window
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fg: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct IconsConfig {
+    #[serde(default)]
+    pub set: IconSet,
+    /// Glyphs replacing individual icons, keyed like `"battery.charging"` or `"app.browser"`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub overrides: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum IconSet {
+    /// Nerd Font glyphs
+    #[default]
+    Nerd,
+    /// Unicode emoji, for systems without a Nerd Font
+    Emoji,
+    /// Short plain-text labels
+    Ascii,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+pub struct LayoutConfig {
+    /// Arrangement of sections; defaults to left, middle and right in a single row
+    #[serde(default)]
+    pub root: LayoutNode,
+    /// Size constraint for each named section
+    #[serde(default)]
+    pub sections: BTreeMap<String, SectionConstraint>,
+}
+
+/// A section name or a nested group of nodes
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(untagged)]
+pub enum LayoutNode {
+    Section(String),
+    Group(LayoutGroup),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum LayoutGroup {
+    /// Children placed side by side
+    Horizontal(Vec<LayoutNode>),
+    /// Children stacked as rows
+    Vertical(Vec<LayoutNode>),
+}
+
+impl Default for LayoutNode {
+    fn default() -> Self {
+        LayoutNode::Group(LayoutGroup::Horizontal(vec![
+            LayoutNode::Section("left".to_string()),
+            LayoutNode::Section("middle".to_string()),
+            LayoutNode::Section("right".to_string()),
+        ]))
+    }
+}
+
+impl LayoutNode {
+    /// Names of the sections under this node, in reading order
+    pub fn sections(&self) -> Vec<&str> {
+        match self {
+            LayoutNode::Section(name) => vec![name],
+            LayoutNode::Group(
+                LayoutGroup::Horizontal(children) | LayoutGroup::Vertical(children),
+            ) => children.iter().flat_map(LayoutNode::sections).collect(),
+        }
+    }
+}
+
+/// Size constraint for a section along its group's direction, in cells unless stated otherwise
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum SectionConstraint {
+    /// Inner sections of a row fit their content, centred between outer sections that share the rest
+    #[default]
+    Auto,
+    Min(u16),
+    Max(u16),
+    Length(u16),
+    Percentage(u16),
+    /// Proportional share of the remaining space
+    Fill(u16),
+}
+
+/// Components for each named section
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(transparent)]
 pub struct BarsConfig {
-    pub left: Vec<ComponentConfig>,
-    pub middle: Vec<ComponentConfig>,
-    pub right: Vec<ComponentConfig>,
+    pub sections: BTreeMap<String, Vec<ComponentConfig>>,
+}
+
+fn default_colorize() -> bool {
+    true
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
+            include: Vec::new(),
             bars: BarsConfig {
-                left: vec![
-                    ComponentConfig::String("workspaces".to_string()),
-                    ComponentConfig::String("windows".to_string()),
-                ],
-                middle: vec![
-                    ComponentConfig::String("time".to_string()),
-                    ComponentConfig::String("separator".to_string()),
-                    ComponentConfig::String("weather".to_string()),
-                ],
-                right: vec![
-                    ComponentConfig::String("temperature".to_string()),
-                    ComponentConfig::String("space".to_string()),
-                    ComponentConfig::String("cpu".to_string()),
-                    ComponentConfig::String("space".to_string()),
-                    ComponentConfig::String("ram".to_string()),
-                    ComponentConfig::String("separator".to_string()),
-                    ComponentConfig::String("wifi".to_string()),
-                    ComponentConfig::String("separator".to_string()),
-                    ComponentConfig::String("brightness".to_string()),
-                    ComponentConfig::String("space".to_string()),
-                    ComponentConfig::String("volume".to_string()),
-                    ComponentConfig::String("separator".to_string()),
-                    ComponentConfig::String("battery".to_string()),
-                ],
+                sections: BTreeMap::from([
+                    (
+                        "left".to_string(),
+                        vec![
+                            ComponentConfig::String("workspaces".to_string()),
+                            ComponentConfig::String("windows".to_string()),
+                        ],
+                    ),
+                    (
+                        "middle".to_string(),
+                        vec![
+                            ComponentConfig::String("time".to_string()),
+                            ComponentConfig::String("separator".to_string()),
+                            ComponentConfig::String("weather".to_string()),
+                        ],
+                    ),
+                    (
+                        "right".to_string(),
+                        vec![
+                            ComponentConfig::String("temperature".to_string()),
+                            ComponentConfig::String("space".to_string()),
+                            ComponentConfig::String("cpu".to_string()),
+                            ComponentConfig::String("space".to_string()),
+                            ComponentConfig::String("ram".to_string()),
+                            ComponentConfig::String("separator".to_string()),
+                            ComponentConfig::String("wifi".to_string()),
+                            ComponentConfig::String("separator".to_string()),
+                            ComponentConfig::String("brightness".to_string()),
+                            ComponentConfig::String("space".to_string()),
+                            ComponentConfig::String("volume".to_string()),
+                            ComponentConfig::String("separator".to_string()),
+                            ComponentConfig::String("battery".to_string()),
+                        ],
+                    ),
+                ]),
             },
             colorize: true,
+            layout: LayoutConfig::default(),
+            segments: SegmentsConfig::default(),
+            icons: IconsConfig::default(),
+            apps: Vec::new(),
+            theme: BTreeMap::new(),
+            profiles: BTreeMap::new(),
+            panel: PanelConfig::default(),
+            outputs: BTreeMap::new(),
+            active_profile: None,
+            requested_profile: None,
+            active_output: None,
         }
     }
 }
 
 impl Config {
-    pub fn load() -> color_eyre::Result<Self> {
+    pub fn load(profile: Option<&str>, output: Option<&str>) -> color_eyre::Result<Self> {
         let config_path = Self::config_path();
 
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = serde_json::from_str(&content)?;
-            Ok(config)
+            Self::from_file(&config_path, profile, output)
         } else {
             let default_config = Config::default();
             default_config.save()?;
@@ -94,7 +678,50 @@ impl Config {
         Ok(())
     }
 
-    fn config_path() -> std::path::PathBuf {
+    /// JSON Schema describing `bar.json`, for editor completion and validation
+    pub fn json_schema() -> Value {
+        let mut schema = schemars::schema_for!(Config).to_value();
+
+        // Limit each built-in component to its own options; Lua components accept any
+        let rules: Vec<Value> = BUILTIN_COMPONENTS
+            .iter()
+            .map(|name| {
+                let properties: serde_json::Map<String, Value> = COMMON_OPTIONS
+                    .iter()
+                    .chain(component_options(name))
+                    .filter(|option| {
+                        accepts_format(name) || !matches!(**option, "format" | "format_alt")
+                    })
+                    .map(|option| (option.to_string(), Value::Bool(true)))
+                    .collect();
+                let mut required = vec!["name"];
+                if *name == "exec" {
+                    required.push("command");
+                }
+
+                serde_json::json!({
+                    "if": {
+                        "properties": { "name": { "const": name } },
+                        "required": ["name"],
+                    },
+                    "then": {
+                        "properties": properties,
+                        "required": required,
+                        "additionalProperties": false,
+                    },
+                })
+            })
+            .collect();
+        if let Some(options) = schema.pointer_mut("/$defs/ComponentOptions")
+            && let Some(options) = options.as_object_mut()
+        {
+            options.insert("allOf".to_string(), Value::Array(rules));
+        }
+
+        schema
+    }
+
+    pub fn config_path() -> std::path::PathBuf {
         let home_dir = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
         std::path::PathBuf::from(home_dir)
             .join(".config")
@@ -103,11 +730,24 @@ impl Config {
     }
 
     pub fn get_components_for_bar(&self, bar: &str) -> Option<&Vec<ComponentConfig>> {
-        match bar {
-            "left" => Some(&self.bars.left),
-            "middle" => Some(&self.bars.middle),
-            "right" => Some(&self.bars.right),
-            _ => None,
+        self.bars.sections.get(bar)
+    }
+
+    pub fn get_layout_for_bar(&self, bar: &str) -> SectionConstraint {
+        self.layout.sections.get(bar).copied().unwrap_or_default()
+    }
+
+    /// Style for a component state, from the theme or the built-in defaults
+    pub fn state_style(&self, state: &str) -> Style {
+        if let Some(style) = self.theme.get(state) {
+            return style.to_style();
+        }
+
+        match state {
+            "warning" => Style::default().fg(Color::Yellow),
+            "critical" => Style::default().fg(Color::Red),
+            "urgent" => Style::default().bg(Color::Red).fg(Color::White),
+            _ => Style::default(),
         }
     }
 
@@ -115,48 +755,471 @@ impl Config {
         let config_path = Self::config_path();
 
         if config_path.exists() {
-            let content = std::fs::read_to_string(&config_path)?;
-            let config: Config = serde_json::from_str(&content)?;
-            Ok(config)
+            Self::from_file(
+                &config_path,
+                self.requested_profile.as_deref(),
+                self.active_output.as_deref(),
+            )
         } else {
             Ok(Self::default())
         }
     }
+
+    /// Read a config file, merge its includes and apply the selected profile and output
+    fn from_file(
+        path: &Path,
+        profile: Option<&str>,
+        output: Option<&str>,
+    ) -> color_eyre::Result<Self> {
+        let mut value = read_with_includes(path, &mut Vec::new())?;
+
+        let profiles: BTreeMap<String, ProfileConfig> = match value.get("profiles") {
+            Some(profiles) => serde_json::from_value(profiles.clone())?,
+            None => BTreeMap::new(),
+        };
+
+        let active_profile = match profile {
+            Some(name) => {
+                if !profiles.contains_key(name) {
+                    return Err(color_eyre::eyre::eyre!("Unknown profile: {}", name));
+                }
+                Some(name.to_string())
+            }
+            None => current_hostname().and_then(|hostname| {
+                profiles
+                    .iter()
+                    .find(|(_, profile)| profile.hostnames.contains(&hostname))
+                    .map(|(name, _)| name.clone())
+            }),
+        };
+
+        if let Some(profile) = active_profile.as_ref().and_then(|name| profiles.get(name)) {
+            merge_values(&mut value, Value::Object(profile.overrides.clone()));
+        }
+
+        // Output overrides may come from the profile, so read them after it is merged
+        if let Some(output) = output {
+            let outputs: BTreeMap<String, OutputConfig> = match value.get("outputs") {
+                Some(outputs) => serde_json::from_value(outputs.clone())?,
+                None => BTreeMap::new(),
+            };
+            if let Some(output_config) = outputs.get(output) {
+                merge_values(&mut value, Value::Object(output_config.overrides.clone()));
+            }
+        }
+
+        let mut config: Config = serde_json::from_value(value)?;
+        config.active_profile = active_profile;
+        config.requested_profile = profile.map(str::to_string);
+        config.active_output = output.map(str::to_string);
+        Ok(config)
+    }
+}
+
+/// Load a JSON file with its `include` fragments merged underneath it
+fn read_with_includes(path: &Path, seen: &mut Vec<PathBuf>) -> color_eyre::Result<Value> {
+    let canonical = path.canonicalize()?;
+    if seen.contains(&canonical) {
+        return Err(color_eyre::eyre::eyre!(
+            "Config include cycle detected at {}",
+            path.display()
+        ));
+    }
+    seen.push(canonical);
+
+    let content = std::fs::read_to_string(path)?;
+    let value: Value = serde_json::from_str(&content)
+        .map_err(|e| color_eyre::eyre::eyre!("Failed to parse {}: {}", path.display(), e))?;
+
+    let includes: Vec<String> = match value.get("include") {
+        Some(include) => serde_json::from_value(include.clone())?,
+        None => Vec::new(),
+    };
+
+    let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+    let mut merged = Value::Object(Map::new());
+    for include in includes {
+        let fragment = read_with_includes(&base_dir.join(include), seen)?;
+        merge_values(&mut merged, fragment);
+    }
+    merge_values(&mut merged, value);
+
+    seen.pop();
+    Ok(merged)
+}
+
+/// Deep-merge `overlay` into `base`; objects merge key by key, everything else is replaced
+fn merge_values(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (Value::Object(base), Value::Object(overlay)) => {
+            for (key, value) in overlay {
+                match base.get_mut(&key) {
+                    Some(existing) => merge_values(existing, value),
+                    None => {
+                        base.insert(key, value);
+                    }
+                }
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+fn current_hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let result = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if result != 0 {
+        return None;
+    }
+
+    let len = buf.iter().position(|&b| b == 0).unwrap_or(buf.len());
+    String::from_utf8(buf[..len].to_vec()).ok()
 }
 
 impl ComponentConfig {
     pub fn name(&self) -> &str {
         match self {
             ComponentConfig::String(name) => name,
+            ComponentConfig::Group(_) => "",
             ComponentConfig::Object(options) => &options.name,
         }
     }
 
-    pub fn sparkline(&self) -> Option<bool> {
+    /// Identifies the component instance; identically configured entries share one
+    pub fn key(&self) -> String {
         match self {
-            ComponentConfig::String(_) => None,
-            ComponentConfig::Object(options) => options.sparkline,
+            ComponentConfig::String(name) => name.clone(),
+            _ => serde_json::to_string(self).unwrap_or_default(),
         }
     }
 
-    pub fn sparkline_length(&self) -> Option<usize> {
+    fn options(&self) -> Option<&ComponentOptions> {
         match self {
-            ComponentConfig::String(_) => None,
-            ComponentConfig::Object(options) => options.sparkline_length,
+            ComponentConfig::Object(options) => Some(options),
+            _ => None,
         }
     }
 
-    pub fn sparkline_update_freq(&self) -> Option<u64> {
+    /// The individual components this entry stands for, looking inside segment groups
+    pub fn leaves(&self) -> Vec<&ComponentConfig> {
         match self {
-            ComponentConfig::String(_) => None,
-            ComponentConfig::Object(options) => options.sparkline_update_freq,
+            ComponentConfig::Group(group) => group
+                .segment
+                .iter()
+                .flat_map(ComponentConfig::leaves)
+                .collect(),
+            _ => vec![self],
         }
     }
 
+    pub fn id(&self) -> Option<&str> {
+        self.options().and_then(|options| options.id.as_deref())
+    }
+
+    pub fn sparkline(&self) -> Option<bool> {
+        self.options().and_then(|options| options.sparkline)
+    }
+
+    pub fn sparkline_length(&self) -> Option<usize> {
+        self.options().and_then(|options| options.sparkline_length)
+    }
+
+    pub fn sparkline_update_freq(&self) -> Option<u64> {
+        self.options()
+            .and_then(|options| options.sparkline_update_freq)
+    }
+
     pub fn sparkline_logarithmic(&self) -> Option<bool> {
+        self.options()
+            .and_then(|options| options.sparkline_logarithmic)
+    }
+
+    pub fn priority(&self) -> i32 {
         match self {
-            ComponentConfig::String(_) => None,
-            ComponentConfig::Object(options) => options.sparkline_logarithmic,
+            ComponentConfig::String(_) => 0,
+            ComponentConfig::Group(group) => group.priority.unwrap_or(0),
+            ComponentConfig::Object(options) => options.priority.unwrap_or(0),
         }
     }
+
+    pub fn min_width(&self) -> Option<u16> {
+        self.options().and_then(|options| options.min_width)
+    }
+
+    pub fn max_width(&self) -> Option<u16> {
+        self.options().and_then(|options| options.max_width)
+    }
+
+    pub fn glyph(&self) -> Option<&str> {
+        self.options().and_then(|options| options.glyph.as_deref())
+    }
+
+    pub fn format(&self) -> Option<&str> {
+        self.options().and_then(|options| options.format.as_deref())
+    }
+
+    pub fn format_alt(&self) -> Option<&str> {
+        self.options()
+            .and_then(|options| options.format_alt.as_deref())
+    }
+
+    pub fn states(&self) -> Option<Vec<(String, f64)>> {
+        self.options()
+            .and_then(|options| options.states.as_ref())
+            .map(|states| {
+                states
+                    .iter()
+                    .map(|(name, threshold)| (name.clone(), *threshold))
+                    .collect()
+            })
+    }
+
+    pub fn display(&self) -> Option<WindowDisplay> {
+        self.options().and_then(|options| options.display)
+    }
+
+    pub fn title_length(&self) -> Option<usize> {
+        self.options().and_then(|options| options.title_length)
+    }
+
+    pub fn scope(&self) -> Option<WindowScope> {
+        self.options().and_then(|options| options.scope)
+    }
+
+    pub fn group_by_workspace(&self) -> Option<bool> {
+        self.options()
+            .and_then(|options| options.group_by_workspace)
+    }
+
+    pub fn persistent(&self) -> Option<&[String]> {
+        self.options()
+            .and_then(|options| options.persistent.as_deref())
+    }
+
+    pub fn labels(&self) -> Option<&BTreeMap<String, String>> {
+        self.options().and_then(|options| options.labels.as_ref())
+    }
+
+    pub fn show_special(&self) -> Option<bool> {
+        self.options().and_then(|options| options.show_special)
+    }
+
+    pub fn window_count(&self) -> Option<bool> {
+        self.options().and_then(|options| options.window_count)
+    }
+
+    pub fn command(&self) -> Option<&str> {
+        self.options()
+            .and_then(|options| options.command.as_deref())
+    }
+
+    pub fn interval(&self) -> Option<u64> {
+        self.options().and_then(|options| options.interval)
+    }
+
+    pub fn streaming(&self) -> Option<bool> {
+        self.options().and_then(|options| options.streaming)
+    }
+
+    pub fn json(&self) -> Option<bool> {
+        self.options().and_then(|options| options.json)
+    }
+
+    pub fn show_when(&self) -> Option<&str> {
+        self.options()
+            .and_then(|options| options.show_when.as_deref())
+    }
+
+    pub fn hide_when(&self) -> Option<&str> {
+        self.options()
+            .and_then(|options| options.hide_when.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// A fresh directory for the config files of one test
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "catfood-config-test-{}-{}",
+            std::process::id(),
+            name
+        ));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(dir: &Path, name: &str, value: Value) -> PathBuf {
+        let path = dir.join(name);
+        std::fs::write(&path, value.to_string()).unwrap();
+        path
+    }
+
+    fn left(config: &Config) -> Vec<String> {
+        config.bars.sections["left"]
+            .iter()
+            .map(|component| component.name().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn merge_values_merges_objects_and_replaces_everything_else() {
+        let mut base = json!({ "a": { "x": 1, "y": [1, 2] }, "b": true });
+        merge_values(
+            &mut base,
+            json!({ "a": { "y": [3], "z": "new" }, "b": null }),
+        );
+        assert_eq!(
+            base,
+            json!({ "a": { "x": 1, "y": [3], "z": "new" }, "b": null })
+        );
+    }
+
+    #[test]
+    fn includes_are_relative_and_merged_underneath() {
+        let dir = temp_dir("includes");
+        std::fs::create_dir_all(dir.join("parts")).unwrap();
+        write(
+            &dir,
+            "parts/common.json",
+            json!({ "include": ["colors.json"], "colorize": false, "panel": { "lines": 2 } }),
+        );
+        write(
+            &dir,
+            "parts/colors.json",
+            json!({ "theme": { "warning": { "fg": "blue" } } }),
+        );
+        let path = write(
+            &dir,
+            "bar.json",
+            json!({ "include": ["parts/common.json"], "panel": { "lines": 3 } }),
+        );
+
+        let value = read_with_includes(&path, &mut Vec::new()).unwrap();
+        assert_eq!(value["colorize"], json!(false));
+        assert_eq!(value["panel"]["lines"], json!(3));
+        assert_eq!(value["theme"]["warning"]["fg"], json!("blue"));
+    }
+
+    #[test]
+    fn include_cycles_are_reported() {
+        let dir = temp_dir("cycle");
+        write(&dir, "a.json", json!({ "include": ["b.json"] }));
+        let path = write(&dir, "b.json", json!({ "include": ["a.json"] }));
+
+        let error = read_with_includes(&path, &mut Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("cycle"), "{}", error);
+    }
+
+    #[test]
+    fn output_overrides_apply_after_the_profile() {
+        let dir = temp_dir("overrides");
+        let path = write(
+            &dir,
+            "bar.json",
+            json!({
+                "bars": { "left": ["cpu"] },
+                "colorize": true,
+                "profiles": {
+                    "laptop": {
+                        "bars": { "left": ["battery"] },
+                        "colorize": false,
+                        "outputs": { "eDP-1": { "bars": { "left": ["ram"] } } },
+                    },
+                },
+                "outputs": { "eDP-1": { "colorize": true } },
+            }),
+        );
+
+        let base = Config::from_file(&path, None, None).unwrap();
+        assert_eq!(left(&base), ["cpu"]);
+
+        let profile = Config::from_file(&path, Some("laptop"), None).unwrap();
+        assert_eq!(left(&profile), ["battery"]);
+        assert!(!profile.colorize);
+
+        // The profile's outputs merge over the base ones, then apply over the profile
+        let output = Config::from_file(&path, Some("laptop"), Some("eDP-1")).unwrap();
+        assert_eq!(left(&output), ["ram"]);
+        assert!(output.colorize);
+        assert_eq!(output.active_output.as_deref(), Some("eDP-1"));
+    }
+
+    #[test]
+    fn unknown_explicit_profiles_are_errors() {
+        let dir = temp_dir("unknown-profile");
+        let path = write(&dir, "bar.json", json!({ "bars": {}, "colorize": true }));
+        assert!(Config::from_file(&path, Some("missing"), None).is_err());
+    }
+
+    #[test]
+    fn hostname_profiles_are_matched_again_on_reload() {
+        let Some(hostname) = current_hostname() else {
+            return;
+        };
+        let dir = temp_dir("hostname-profile");
+        let profiles = |name: &str| {
+            json!({
+                "bars": { "left": ["cpu"] },
+                "colorize": true,
+                "profiles": { name: { "hostnames": [hostname], "bars": { "left": [name] } } },
+            })
+        };
+        let path = write(&dir, "bar.json", profiles("ram"));
+
+        let config = Config::from_file(&path, None, None).unwrap();
+        assert_eq!(config.active_profile.as_deref(), Some("ram"));
+        assert_eq!(config.requested_profile, None);
+
+        // Renaming the matched profile must not break the next reload
+        write(&dir, "bar.json", profiles("wifi"));
+        let reloaded = Config::from_file(&path, config.requested_profile.as_deref(), None).unwrap();
+        assert_eq!(reloaded.active_profile.as_deref(), Some("wifi"));
+        assert_eq!(left(&reloaded), ["wifi"]);
+    }
+
+    #[test]
+    fn documented_configs_match_the_schema() {
+        let schema = Config::json_schema();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+
+        let docs = include_str!("../../../CONFIG_EXAMPLE.md");
+        for block in docs.split("```json\n").skip(1) {
+            let block = block.split("```").next().unwrap_or_default();
+            let value: Value = serde_json::from_str(block).unwrap();
+
+            let errors: Vec<_> = validator
+                .iter_errors(&value)
+                .map(|e| e.to_string())
+                .collect();
+            assert!(errors.is_empty(), "{}\n{:?}", block, errors);
+            serde_json::from_value::<Config>(value).unwrap();
+        }
+    }
+
+    #[test]
+    fn fragments_match_the_schema() {
+        let schema = Config::json_schema();
+        let validator = jsonschema::validator_for(&schema).unwrap();
+        assert!(validator.is_valid(&json!({ "include": ["common.json"] })));
+        assert!(validator.is_valid(&serde_json::to_value(Config::default()).unwrap()));
+        assert!(
+            !validator.is_valid(&json!({ "bars": { "left": [{ "name": "cpu", "glyph": "x" }] } }))
+        );
+    }
+
+    #[test]
+    fn only_single_label_components_take_a_format() {
+        let validator = jsonschema::validator_for(&Config::json_schema()).unwrap();
+        let component = |component| json!({ "bars": { "left": [component] } });
+        assert!(validator.is_valid(&component(json!({ "name": "cpu", "format": "{percent}" }))));
+        assert!(!validator.is_valid(&component(
+            json!({ "name": "workspaces", "format": "{active}" })
+        )));
+        assert!(!validator.is_valid(&component(json!({ "name": "space", "format_alt": "-" }))));
+    }
 }
@@ -0,0 +1,58 @@
+/// Replace `{name}` placeholders in `template` with the matching values.
+///
+/// Unknown placeholders are left untouched so typos stay visible in the bar.
+pub fn apply(template: &str, values: &[(&str, String)]) -> String {
+    let mut result = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find('{') {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 1..];
+
+        match after.find('}') {
+            Some(end) => {
+                let name = &after[..end];
+                match values.iter().find(|(key, _)| *key == name) {
+                    Some((_, value)) => result.push_str(value),
+                    None => result.push_str(&rest[start..start + end + 2]),
+                }
+                rest = &after[end + 1..];
+            }
+            None => {
+                result.push_str(&rest[start..]);
+                rest = "";
+            }
+        }
+    }
+
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values() -> Vec<(&'static str, String)> {
+        vec![("icon", "󰁹".to_string()), ("percent", "80".to_string())]
+    }
+
+    #[test]
+    fn placeholders_are_replaced() {
+        assert_eq!(apply("{icon} {percent}%", &values()), "󰁹 80%");
+        assert_eq!(apply("{percent}{percent}", &values()), "8080");
+        assert_eq!(apply("no placeholders", &values()), "no placeholders");
+    }
+
+    #[test]
+    fn unknown_placeholders_are_left_as_written() {
+        assert_eq!(apply("{percnt}% {icon}", &values()), "{percnt}% 󰁹");
+        assert_eq!(apply("{}", &values()), "{}");
+    }
+
+    #[test]
+    fn unclosed_braces_are_kept() {
+        assert_eq!(apply("{icon} {percent", &values()), "󰁹 {percent");
+        assert_eq!(apply("{{icon}}", &values()), "{{icon}}");
+    }
+}
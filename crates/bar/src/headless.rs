@@ -0,0 +1,600 @@
+//! Running the bar without a terminal, feeding its components to other status bars.
+//!
+//! With `--output i3bar` the bar speaks the i3bar protocol on stdout, one block per
+//! component or segment group, and reads click events from stdin, so swaybar or i3bar can
+//! use it as their `status_command`. The `text`, `tmux`, `ansi` and `json` formats write one
+//! status line per update instead, for tmux status lines, shell prompts and scripts.
+
+use crate::component_manager::ComponentManager;
+use crate::{App, logging};
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Modifier},
+    text::Span,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::io::Write;
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, BufReader};
+use tokio::runtime::Runtime;
+use tokio::sync::mpsc;
+
+/// How the bar writes its output when not drawing into a terminal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum OutputFormat {
+    /// The i3bar JSON protocol, for swaybar and i3bar
+    I3bar,
+    /// Plain text without colors
+    Text,
+    /// tmux status line markup, e.g. `#[fg=red]`
+    Tmux,
+    /// Text colored with ANSI escape sequences
+    Ansi,
+    /// Each section's components and their styled spans as JSON
+    Json,
+}
+
+/// One entry of an i3bar status line
+#[derive(Debug, Serialize)]
+struct Block {
+    full_text: String,
+    /// Section the block belongs to
+    name: String,
+    /// Position of the block within its section
+    instance: String,
+    /// Component or segment group key, for routing clicks
+    #[serde(skip)]
+    key: String,
+    markup: &'static str,
+    separator: bool,
+    separator_block_width: u16,
+}
+
+/// A click reported by the status bar on stdin
+#[derive(Debug, Deserialize)]
+struct ClickEvent {
+    name: String,
+    #[serde(default)]
+    instance: String,
+    button: u8,
+}
+
+/// Run the bar, writing `format` to stdout every `interval` until stdout is closed,
+/// or just once when `once` is set
+pub fn run(
+    format: OutputFormat,
+    once: bool,
+    interval: Duration,
+    profile: Option<&str>,
+    output: Option<&str>,
+) -> color_eyre::Result<()> {
+    color_eyre::install()?;
+    // tokio's interval panics on zero
+    let interval = interval.max(Duration::from_secs(1));
+
+    let rt = Runtime::new()?;
+    rt.block_on(async {
+        let mut component_manager = ComponentManager::new(profile, output)?;
+        let (reload_tx, mut reload_rx) = mpsc::channel(10);
+
+        if once {
+            settle(&mut component_manager).await;
+        } else {
+            App::start_config_watcher(reload_tx)?;
+        }
+
+        match format {
+            OutputFormat::I3bar => {
+                run_i3bar(&mut component_manager, &mut reload_rx, once, interval).await
+            }
+            _ => {
+                run_status_line(
+                    format,
+                    &mut component_manager,
+                    &mut reload_rx,
+                    once,
+                    interval,
+                )
+                .await
+            }
+        }
+    })
+}
+
+/// Longest `--once` waits for background components such as `exec` and `weather`
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Give components that sample over time a moment to get data, then wait for background ones
+/// to finish their first run, up to [`SETTLE_TIMEOUT`]
+async fn settle(component_manager: &mut ComponentManager) {
+    let deadline = tokio::time::Instant::now() + SETTLE_TIMEOUT;
+    let _ = component_manager.update();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    while !component_manager.is_ready() && tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    if let Err(e) = component_manager.update() {
+        logging::log_system_error("Component Manager", &format!("{}", e));
+    }
+}
+
+/// Write a status line every `interval`, or a single one when `once` is set
+async fn run_status_line(
+    format: OutputFormat,
+    component_manager: &mut ComponentManager,
+    reload_rx: &mut mpsc::Receiver<()>,
+    once: bool,
+    interval: Duration,
+) -> color_eyre::Result<()> {
+    let mut stdout = std::io::stdout();
+    let mut interval = tokio::time::interval(interval);
+
+    loop {
+        if !once {
+            tokio::select! {
+                _ = interval.tick() => {
+                    if let Err(e) = component_manager.update() {
+                        logging::log_system_error("Component Manager", &format!("{}", e));
+                    }
+                }
+                _ = reload_rx.recv() => {
+                    if let Err(e) = component_manager.reload() {
+                        logging::log_config_error(&format!("Failed to reload configuration: {}", e));
+                    }
+                }
+            }
+        }
+
+        let line = status_line(format, component_manager);
+        // Whoever reads the output closing it is the signal to quit
+        if writeln!(stdout, "{}", line).is_err() || stdout.flush().is_err() || once {
+            return Ok(());
+        }
+    }
+}
+
+/// The whole bar as one line of `format`, sections in layout order
+fn status_line(format: OutputFormat, component_manager: &ComponentManager) -> String {
+    let layout = component_manager.get_layout();
+    let sections = layout.root.sections();
+
+    if format == OutputFormat::Json {
+        let sections: Vec<_> = sections
+            .into_iter()
+            .map(|section| {
+                let segments: Vec<_> = component_manager
+                    .get_bar_segments(section, u16::MAX, Alignment::Left)
+                    .into_iter()
+                    .map(|segment| {
+                        let spans: Vec<_> = segment.spans.iter().map(json_span).collect();
+                        json!({
+                            "text": segment.spans.iter().map(|span| span.content.as_ref()).collect::<String>(),
+                            "spans": spans,
+                        })
+                    })
+                    .collect();
+                json!({ "section": section, "segments": segments })
+            })
+            .collect();
+        return json!(sections).to_string();
+    }
+
+    let render_span = match format {
+        OutputFormat::Tmux => tmux_span,
+        OutputFormat::Ansi => ansi_span,
+        _ => |span: &Span| span.content.to_string(),
+    };
+    sections
+        .into_iter()
+        .map(|section| {
+            component_manager
+                .get_bar_spans(section, u16::MAX, Alignment::Left)
+                .iter()
+                .map(render_span)
+                .collect::<String>()
+        })
+        .filter(|text| !text.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A span's text and style as JSON
+fn json_span(span: &Span) -> serde_json::Value {
+    let style = span.style;
+    json!({
+        "text": span.content,
+        "fg": style.fg.and_then(color_hex),
+        "bg": style.bg.and_then(color_hex),
+        "bold": style.add_modifier.contains(Modifier::BOLD),
+        "italic": style.add_modifier.contains(Modifier::ITALIC),
+        "underline": style.add_modifier.contains(Modifier::UNDERLINED),
+        "reversed": style.add_modifier.contains(Modifier::REVERSED),
+    })
+}
+
+/// A span with tmux style markup, e.g. `#[fg=red,bold]text#[default]`
+fn tmux_span(span: &Span) -> String {
+    // A lone `#` would start a tmux format
+    let text = span.content.replace('#', "##");
+    let style = span.style;
+
+    let mut attributes = Vec::new();
+    if let Some(fg) = style.fg.and_then(tmux_color) {
+        attributes.push(format!("fg={}", fg));
+    }
+    if let Some(bg) = style.bg.and_then(tmux_color) {
+        attributes.push(format!("bg={}", bg));
+    }
+    for (modifier, name) in [
+        (Modifier::BOLD, "bold"),
+        (Modifier::DIM, "dim"),
+        (Modifier::ITALIC, "italics"),
+        (Modifier::UNDERLINED, "underscore"),
+        (Modifier::REVERSED, "reverse"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            attributes.push(name.to_string());
+        }
+    }
+
+    if attributes.is_empty() {
+        text
+    } else {
+        format!("#[{}]{}#[default]", attributes.join(","), text)
+    }
+}
+
+/// A terminal color as tmux names it
+fn tmux_color(color: Color) -> Option<String> {
+    const NAMES: [&str; 16] = [
+        "black",
+        "red",
+        "green",
+        "yellow",
+        "blue",
+        "magenta",
+        "cyan",
+        "white",
+        "brightblack",
+        "brightred",
+        "brightgreen",
+        "brightyellow",
+        "brightblue",
+        "brightmagenta",
+        "brightcyan",
+        "brightwhite",
+    ];
+
+    match color {
+        Color::Reset => None,
+        Color::Indexed(index) => Some(format!("colour{}", index)),
+        Color::Rgb(..) => color_hex(color),
+        _ => basic_index(color).map(|index| NAMES[index].to_string()),
+    }
+}
+
+/// A span wrapped in ANSI SGR escape sequences
+fn ansi_span(span: &Span) -> String {
+    let style = span.style;
+
+    let mut codes = Vec::new();
+    if let Some(fg) = style.fg.and_then(|color| ansi_color(color, 30)) {
+        codes.push(fg);
+    }
+    if let Some(bg) = style.bg.and_then(|color| ansi_color(color, 40)) {
+        codes.push(bg);
+    }
+    for (modifier, code) in [
+        (Modifier::BOLD, "1"),
+        (Modifier::DIM, "2"),
+        (Modifier::ITALIC, "3"),
+        (Modifier::UNDERLINED, "4"),
+        (Modifier::REVERSED, "7"),
+    ] {
+        if style.add_modifier.contains(modifier) {
+            codes.push(code.to_string());
+        }
+    }
+
+    if codes.is_empty() {
+        span.content.to_string()
+    } else {
+        format!("\x1b[{}m{}\x1b[0m", codes.join(";"), span.content)
+    }
+}
+
+/// SGR parameters selecting `color`, where `base` is 30 for foreground or 40 for background
+fn ansi_color(color: Color, base: u8) -> Option<String> {
+    match color {
+        Color::Reset => None,
+        Color::Indexed(index) => Some(format!("{};5;{}", base + 8, index)),
+        Color::Rgb(r, g, b) => Some(format!("{};2;{};{};{}", base + 8, r, g, b)),
+        _ => basic_index(color).map(|index| match index {
+            0..8 => (base as usize + index).to_string(),
+            _ => (base as usize + 60 + index - 8).to_string(),
+        }),
+    }
+}
+
+/// Position of a named color in the 16-color palette
+fn basic_index(color: Color) -> Option<usize> {
+    BASIC_COLORS.iter().position(|basic| *basic == color)
+}
+
+/// Stream i3bar status lines every `interval`, handling clicks as they arrive
+async fn run_i3bar(
+    component_manager: &mut ComponentManager,
+    reload_rx: &mut mpsc::Receiver<()>,
+    once: bool,
+    interval: Duration,
+) -> color_eyre::Result<()> {
+    let mut stdout = std::io::stdout();
+    writeln!(stdout, r#"{{"version":1,"click_events":true}}"#)?;
+    writeln!(stdout, "[")?;
+
+    let mut clicks = BufReader::new(tokio::io::stdin()).lines();
+    let mut stdin_open = !once;
+    let mut interval = tokio::time::interval(interval);
+    let mut blocks = Vec::new();
+
+    loop {
+        tokio::select! {
+            _ = interval.tick() => {
+                if let Err(e) = component_manager.update() {
+                    logging::log_system_error("Component Manager", &format!("{}", e));
+                }
+            }
+            _ = reload_rx.recv() => {
+                if let Err(e) = component_manager.reload() {
+                    logging::log_config_error(&format!("Failed to reload configuration: {}", e));
+                }
+            }
+            line = clicks.next_line(), if stdin_open => {
+                match line {
+                    Ok(Some(line)) => {
+                        // Events arrive as an endless JSON array, one element per line
+                        let event = line.trim().trim_start_matches(['[', ',']);
+                        match serde_json::from_str::<ClickEvent>(event) {
+                            Ok(click) if click.button == 1 => {
+                                if let Some(block) = blocks.iter().find(|block: &&Block| {
+                                    block.name == click.name && block.instance == click.instance
+                                }) {
+                                    component_manager.on_click(&block.key);
+                                }
+                            }
+                            _ => continue,
+                        }
+                    }
+                    _ => stdin_open = false,
+                }
+            }
+        }
+
+        blocks = i3bar_blocks(component_manager);
+        // The status bar closing our stdout is the signal to quit
+        if writeln!(stdout, "{},", serde_json::to_string(&blocks)?).is_err()
+            || stdout.flush().is_err()
+            || once
+        {
+            return Ok(());
+        }
+    }
+}
+
+/// The bar's sections as i3bar blocks, in layout order
+fn i3bar_blocks(component_manager: &ComponentManager) -> Vec<Block> {
+    let mut blocks = Vec::new();
+    for section in component_manager.get_layout().root.sections() {
+        let segments = component_manager.get_bar_segments(section, u16::MAX, Alignment::Left);
+        let count = segments.len();
+        for (i, segment) in segments.into_iter().enumerate() {
+            // Separate sections from each other, not the components within them
+            let last = i + 1 == count;
+            blocks.push(Block {
+                full_text: segment.spans.iter().map(pango_span).collect(),
+                name: section.to_string(),
+                instance: i.to_string(),
+                key: segment.key,
+                markup: "pango",
+                separator: last,
+                separator_block_width: if last { 9 } else { 0 },
+            });
+        }
+    }
+    blocks
+}
+
+/// A span as Pango markup, keeping its colors and emphasis
+fn pango_span(span: &Span) -> String {
+    let text = escape_markup(&span.content);
+    let style = span.style;
+
+    let mut attributes = String::new();
+    if let Some(fg) = style.fg.and_then(color_hex) {
+        attributes.push_str(&format!(r#" foreground="{}""#, fg));
+    }
+    if let Some(bg) = style.bg.and_then(color_hex) {
+        attributes.push_str(&format!(r#" background="{}""#, bg));
+    }
+    if style.add_modifier.contains(Modifier::BOLD) {
+        attributes.push_str(r#" weight="bold""#);
+    }
+    if style.add_modifier.contains(Modifier::ITALIC) {
+        attributes.push_str(r#" style="italic""#);
+    }
+    if style.add_modifier.contains(Modifier::UNDERLINED) {
+        attributes.push_str(r#" underline="single""#);
+    }
+
+    if attributes.is_empty() {
+        text
+    } else {
+        format!("<span{}>{}</span>", attributes, text)
+    }
+}
+
+fn escape_markup(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// A terminal color as `#rrggbb`
+pub fn color_hex(color: Color) -> Option<String> {
+    let (r, g, b) = color_rgb(color)?;
+    Some(format!("#{:02x}{:02x}{:02x}", r, g, b))
+}
+
+/// RGB value of a terminal color, using the xterm palette for named and indexed colors
+pub fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Black => (0x00, 0x00, 0x00),
+        Color::Red => (0xcd, 0x00, 0x00),
+        Color::Green => (0x00, 0xcd, 0x00),
+        Color::Yellow => (0xcd, 0xcd, 0x00),
+        Color::Blue => (0x00, 0x00, 0xee),
+        Color::Magenta => (0xcd, 0x00, 0xcd),
+        Color::Cyan => (0x00, 0xcd, 0xcd),
+        Color::Gray => (0xe5, 0xe5, 0xe5),
+        Color::DarkGray => (0x7f, 0x7f, 0x7f),
+        Color::LightRed => (0xff, 0x00, 0x00),
+        Color::LightGreen => (0x00, 0xff, 0x00),
+        Color::LightYellow => (0xff, 0xff, 0x00),
+        Color::LightBlue => (0x5c, 0x5c, 0xff),
+        Color::LightMagenta => (0xff, 0x00, 0xff),
+        Color::LightCyan => (0x00, 0xff, 0xff),
+        Color::White => (0xff, 0xff, 0xff),
+        Color::Indexed(index) => match index {
+            0..16 => return color_rgb(BASIC_COLORS[index as usize]),
+            16..232 => {
+                let level = |value: u8| if value == 0 { 0 } else { 55 + value * 40 };
+                let index = index - 16;
+                (level(index / 36), level(index / 6 % 6), level(index % 6))
+            }
+            _ => {
+                let gray = 8 + (index - 232) * 10;
+                (gray, gray, gray)
+            }
+        },
+    };
+    Some(rgb)
+}
+
+/// The first 16 entries of the 256-color palette
+const BASIC_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    #[test]
+    fn color_cube_follows_the_xterm_palette() {
+        assert_eq!(color_rgb(Color::Indexed(16)), Some((0, 0, 0)));
+        assert_eq!(color_rgb(Color::Indexed(21)), Some((0, 0, 255)));
+        assert_eq!(color_rgb(Color::Indexed(67)), Some((95, 135, 175)));
+        assert_eq!(color_rgb(Color::Indexed(196)), Some((255, 0, 0)));
+        assert_eq!(color_rgb(Color::Indexed(231)), Some((255, 255, 255)));
+    }
+
+    #[test]
+    fn grayscale_ramp_follows_the_xterm_palette() {
+        assert_eq!(color_rgb(Color::Indexed(232)), Some((8, 8, 8)));
+        assert_eq!(color_rgb(Color::Indexed(244)), Some((128, 128, 128)));
+        assert_eq!(color_rgb(Color::Indexed(255)), Some((238, 238, 238)));
+    }
+
+    #[test]
+    fn named_and_low_indexed_colors_match() {
+        assert_eq!(color_rgb(Color::Indexed(1)), color_rgb(Color::Red));
+        assert_eq!(color_rgb(Color::Indexed(12)), Some((0x5c, 0x5c, 0xff)));
+        assert_eq!(color_rgb(Color::Rgb(1, 2, 3)), Some((1, 2, 3)));
+        assert_eq!(color_rgb(Color::Reset), None);
+        assert_eq!(color_hex(Color::Indexed(67)).as_deref(), Some("#5f87af"));
+    }
+
+    #[test]
+    fn markup_is_escaped() {
+        assert_eq!(
+            escape_markup(r#"<b>"a" & b</b>"#),
+            "&lt;b&gt;&quot;a&quot; &amp; b&lt;/b&gt;"
+        );
+    }
+
+    #[test]
+    fn pango_spans_keep_colors_and_emphasis() {
+        assert_eq!(pango_span(&Span::raw("a<b")), "a&lt;b");
+        assert_eq!(
+            pango_span(
+                &Span::raw("cpu")
+                    .fg(Color::Red)
+                    .bg(Color::Indexed(244))
+                    .bold()
+            ),
+            r##"<span foreground="#cd0000" background="#808080" weight="bold">cpu</span>"##
+        );
+        assert_eq!(
+            pango_span(&Span::raw("x").italic().underlined()),
+            r#"<span style="italic" underline="single">x</span>"#
+        );
+    }
+
+    #[test]
+    fn tmux_spans_double_hashes() {
+        assert_eq!(tmux_span(&Span::raw("#1 #{x}")), "##1 ##{x}");
+        assert_eq!(
+            tmux_span(
+                &Span::raw("#2")
+                    .fg(Color::LightRed)
+                    .bg(Color::Indexed(238))
+                    .bold()
+            ),
+            "#[fg=brightred,bg=colour238,bold]##2#[default]"
+        );
+        assert_eq!(
+            tmux_span(&Span::raw("x").fg(Color::Rgb(0x12, 0x34, 0x56))),
+            "#[fg=#123456]x#[default]"
+        );
+    }
+
+    #[test]
+    fn ansi_colors_pick_the_right_sgr_codes() {
+        assert_eq!(ansi_color(Color::Red, 30).as_deref(), Some("31"));
+        assert_eq!(ansi_color(Color::White, 30).as_deref(), Some("97"));
+        assert_eq!(ansi_color(Color::DarkGray, 40).as_deref(), Some("100"));
+        assert_eq!(
+            ansi_color(Color::Indexed(208), 30).as_deref(),
+            Some("38;5;208")
+        );
+        assert_eq!(
+            ansi_color(Color::Rgb(1, 2, 3), 40).as_deref(),
+            Some("48;2;1;2;3")
+        );
+        assert_eq!(ansi_color(Color::Reset, 30), None);
+        assert_eq!(
+            ansi_span(&Span::raw("hi").fg(Color::Green).bold()),
+            "\x1b[32;1mhi\x1b[0m"
+        );
+    }
+}
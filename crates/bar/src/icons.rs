@@ -0,0 +1,175 @@
+//! Named icons drawn from the configured icon set.
+//!
+//! Components ask for icons by key (`"battery.charging"`, `"weather.rain"`,
+//! `"app.browser"`); the active set supplies the glyph unless the config
+//! overrides that key.
+
+use crate::config::{IconSet, IconsConfig};
+use std::sync::{LazyLock, RwLock};
+
+static ICONS: LazyLock<RwLock<IconsConfig>> = LazyLock::new(|| RwLock::new(IconsConfig::default()));
+
+/// Make `config` the icon set used by every component
+pub fn configure(config: &IconsConfig) {
+    if let Ok(mut icons) = ICONS.write() {
+        *icons = config.clone();
+    }
+}
+
+/// The glyph for `key` in the active set
+pub fn icon(key: &str) -> String {
+    let Ok(icons) = ICONS.read() else {
+        return builtin(IconSet::Nerd, key).to_string();
+    };
+    match icons.overrides.get(key) {
+        Some(glyph) => glyph.clone(),
+        None => builtin(icons.set, key).to_string(),
+    }
+}
+
+/// The glyph for an application of `category`, preferring its own Nerd Font
+/// glyph when that set is active
+pub fn app_icon(category: &str, nerd_glyph: &str) -> String {
+    let key = format!("app.{}", category);
+    if let Ok(icons) = ICONS.read()
+        && icons.set == IconSet::Nerd
+        && !icons.overrides.contains_key(&key)
+    {
+        return nerd_glyph.to_string();
+    }
+    icon(&key)
+}
+
+fn builtin(set: IconSet, key: &str) -> &'static str {
+    match set {
+        IconSet::Nerd => nerd(key),
+        IconSet::Emoji => emoji(key),
+        IconSet::Ascii => ascii(key),
+    }
+}
+
+fn nerd(key: &str) -> &'static str {
+    match key {
+        "battery.charging" => "󰂄",
+        "battery.discharging" => "󰁹",
+        "cpu" => "󰻠",
+        "ram" => "󰍛",
+        "temperature" => "\u{f2c9}",
+        "brightness" => "󰃠",
+        "volume" => "󰕾",
+        "volume.muted" => "󰝟",
+        "wifi.connected" => "󰤨",
+        "wifi.disconnected" => "󰤮",
+        "weather.clear" => "󰖙",
+        "weather.rain" => "󰖗",
+        "weather.snow" => "󰖘",
+        "weather.storm" => "󰖓",
+        "weather.fog" => "󰖑",
+        "weather.wind" => "󰖝",
+        "weather.cloudy" | "weather.unknown" => "󰖐",
+        "error" => "\u{f071}",
+        "workspace.special" => "\u{f04ce}",
+        "window.fullscreen" => "\u{f0293}",
+        "window.floating" => "\u{f05b2}",
+        "window.pinned" => "\u{f0403}",
+        "window.urgent" => "\u{f0026}",
+        "segment.arrow.open" => "\u{e0b2}",
+        "segment.arrow.close" => "\u{e0b0}",
+        "segment.rounded.open" => "\u{e0b6}",
+        "segment.rounded.close" => "\u{e0b4}",
+        _ => "󰍜",
+    }
+}
+
+fn emoji(key: &str) -> &'static str {
+    match key {
+        "battery.charging" => "🔌",
+        "battery.discharging" => "🔋",
+        "cpu" => "💻",
+        "ram" => "🧠",
+        "temperature" => "🔥",
+        "brightness" => "🔆",
+        "volume" => "🔊",
+        "volume.muted" => "🔇",
+        "wifi.connected" => "📶",
+        "wifi.disconnected" => "📵",
+        "weather.clear" => "🌞",
+        "weather.cloudy" => "⛅",
+        "weather.rain" => "🌧",
+        "weather.snow" => "🌨",
+        "weather.storm" => "🌩",
+        "weather.fog" => "🌫",
+        "weather.wind" => "💨",
+        "weather.unknown" => "🌈",
+        "error" => "❗",
+        "workspace.special" => "✨",
+        "window.fullscreen" => "🔳",
+        "window.floating" => "🎈",
+        "window.pinned" => "📌",
+        "window.urgent" => "🔔",
+        "app.browser" => "🌐",
+        "app.terminal" => "📟",
+        "app.editor" => "📝",
+        "app.document" => "📄",
+        "app.image" => "📷",
+        "app.video" => "🎬",
+        "app.music" => "🎵",
+        "app.graphics" => "🎨",
+        "app.chat" => "💬",
+        "app.mail" => "📧",
+        "app.files" => "📁",
+        "app.system" => "📊",
+        "app.office" => "📑",
+        "app.development" => "🔧",
+        "app.game" => "🎮",
+        // Powerline caps have no emoji equivalent
+        key if key.starts_with("segment.") => "",
+        _ => "🪟",
+    }
+}
+
+fn ascii(key: &str) -> &'static str {
+    match key {
+        "battery.charging" => "CHG",
+        "battery.discharging" => "BAT",
+        "cpu" => "CPU",
+        "ram" => "RAM",
+        "temperature" => "TMP",
+        "brightness" => "BRI",
+        "volume" => "VOL",
+        "volume.muted" => "MUT",
+        "wifi.connected" => "NET",
+        "wifi.disconnected" => "OFF",
+        "weather.clear" => "SUN",
+        "weather.cloudy" => "CLD",
+        "weather.rain" => "RAIN",
+        "weather.snow" => "SNOW",
+        "weather.storm" => "STRM",
+        "weather.fog" => "FOG",
+        "weather.wind" => "WIND",
+        "weather.unknown" => "WX",
+        "error" => "!!",
+        "workspace.special" => "*",
+        "window.fullscreen" => "[F]",
+        "window.floating" => "~",
+        "window.pinned" => "^",
+        "window.urgent" => "!",
+        "app.browser" => "www",
+        "app.terminal" => ">_",
+        "app.editor" => "ed",
+        "app.document" => "doc",
+        "app.image" => "img",
+        "app.video" => "vid",
+        "app.music" => "mus",
+        "app.graphics" => "gfx",
+        "app.chat" => "chat",
+        "app.mail" => "mail",
+        "app.files" => "fm",
+        "app.system" => "sys",
+        "app.office" => "ofc",
+        "app.development" => "dev",
+        "app.game" => "game",
+        key if key.starts_with("segment.") => "",
+        _ => "[]",
+    }
+}
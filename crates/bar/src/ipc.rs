@@ -0,0 +1,290 @@
+//! Control socket for talking to a running bar.
+//!
+//! Each bar listens on `$XDG_RUNTIME_DIR/catfood/bar[-<output>].sock`, or under
+//! `/tmp/catfood-<uid>` without a runtime directory. Clients write one JSON
+//! request per line, e.g. `{"command":"set","id":"deploy","text":"ok"}`, and read back one JSON
+//! response per line: `{"ok":true,"data":...}` or `{"ok":false,"error":"..."}`.
+
+use crate::logging;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt};
+use tokio::sync::{mpsc, oneshot};
+
+/// A command sent to a running bar
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Subcommand)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum Request {
+    /// Reload bar.json
+    Reload,
+    /// Hide the bar, or show it again
+    ToggleVisibility,
+    /// Turn colors on or off, toggling when no value is given
+    SetColorize { value: Option<bool> },
+    /// Update a component now instead of waiting for its interval
+    Refresh { component: String },
+    /// Print the bar's current state as JSON
+    DumpState,
+    /// Set the content of the `text` components with this id
+    Set {
+        id: String,
+        text: String,
+        /// Text color, e.g. `green` or `#a6e3a1`
+        #[arg(long)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        color: Option<String>,
+        /// Extra detail, shown through the `{tooltip}` placeholder
+        #[arg(long)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        tooltip: Option<String>,
+        /// Clear the text again after this many seconds
+        #[arg(long)]
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expire: Option<u64>,
+    },
+}
+
+/// A running bar's answer to a [`Request`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Response {
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl Response {
+    pub fn ok(data: Option<Value>) -> Self {
+        Self {
+            ok: true,
+            data,
+            error: None,
+        }
+    }
+
+    pub fn error(error: impl Into<String>) -> Self {
+        Self {
+            ok: false,
+            data: None,
+            error: Some(error.into()),
+        }
+    }
+}
+
+/// A request waiting for the bar's main loop to answer it
+pub type PendingRequest = (Request, oneshot::Sender<Response>);
+
+/// Directory holding the bars' control sockets; `/tmp` is shared, so there it is per user
+fn get_socket_dir() -> PathBuf {
+    match std::env::var_os("XDG_RUNTIME_DIR") {
+        Some(runtime_dir) => PathBuf::from(runtime_dir).join("catfood"),
+        None => PathBuf::from(format!("/tmp/catfood-{}", unsafe { libc::getuid() })),
+    }
+}
+
+/// Check the socket directory belongs to this user and only they can use it, tightening its
+/// permissions if need be; anyone else could replace the sockets or talk to the bars
+fn check_socket_dir(dir: &Path) -> color_eyre::Result<()> {
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != unsafe { libc::getuid() } {
+        return Err(color_eyre::eyre::eyre!(
+            "{} is not a directory owned by this user, so its control sockets can't be trusted",
+            dir.display()
+        ));
+    }
+    if metadata.mode() & 0o077 != 0 {
+        fs::set_permissions(dir, fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Control socket path for the bar on `output`
+pub fn socket_path(output: Option<&str>) -> PathBuf {
+    let file_name = match output {
+        Some(output) => format!("bar-{}.sock", output),
+        None => "bar.sock".to_string(),
+    };
+    get_socket_dir().join(file_name)
+}
+
+/// Listen on the control socket for the bar on `output`, passing requests to `request_tx`
+/// Must be called from within the bar's tokio runtime
+pub fn serve(
+    output: Option<&str>,
+    request_tx: mpsc::Sender<PendingRequest>,
+) -> color_eyre::Result<()> {
+    let path = socket_path(output);
+    let dir = get_socket_dir();
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(&dir)?;
+    check_socket_dir(&dir)?;
+    // The instance lock is held, so any existing socket is left over from a crashed bar
+    let _ = fs::remove_file(&path);
+    let listener = tokio::net::UnixListener::bind(&path)?;
+
+    tokio::spawn(async move {
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    logging::log_system_error("Control Socket", &format!("{}", e));
+                    continue;
+                }
+            };
+
+            let request_tx = request_tx.clone();
+            tokio::spawn(async move {
+                let (reader, mut writer) = stream.into_split();
+                let mut lines = tokio::io::BufReader::new(reader).lines();
+
+                while let Ok(Some(line)) = lines.next_line().await {
+                    let response = match serde_json::from_str::<Request>(&line) {
+                        Ok(request) => {
+                            let (reply_tx, reply_rx) = oneshot::channel();
+                            if request_tx.send((request, reply_tx)).await.is_err() {
+                                break;
+                            }
+                            reply_rx
+                                .await
+                                .unwrap_or_else(|_| Response::error("bar is shutting down"))
+                        }
+                        Err(e) => Response::error(format!("invalid request: {}", e)),
+                    };
+
+                    let Ok(mut json) = serde_json::to_string(&response) else {
+                        break;
+                    };
+                    json.push('\n');
+                    if writer.write_all(json.as_bytes()).await.is_err() {
+                        break;
+                    }
+                }
+            });
+        }
+    });
+
+    Ok(())
+}
+
+/// Remove the control socket for the bar on `output`
+pub fn remove_socket(output: Option<&str>) {
+    let _ = fs::remove_file(socket_path(output));
+}
+
+/// How long to wait for a bar to answer before giving up on it
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Send `request` to the bar on `output`, or to every running bar when `output` is `None`
+/// Returns each bar's socket path with its response; a bar that can't be reached or doesn't
+/// answer gets an error response, and sockets left behind by crashed bars are removed
+pub fn send(
+    request: &Request,
+    output: Option<&str>,
+) -> color_eyre::Result<Vec<(PathBuf, Response)>> {
+    let dir = get_socket_dir();
+    if dir.exists() {
+        check_socket_dir(&dir)?;
+    }
+
+    let paths = match output {
+        Some(output) => vec![socket_path(Some(output))],
+        None => {
+            let mut paths: Vec<_> = fs::read_dir(&dir)
+                .map(|entries| {
+                    entries
+                        .flatten()
+                        .map(|entry| entry.path())
+                        .filter(|path| {
+                            path.extension().and_then(|ext| ext.to_str()) == Some("sock")
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+            paths.sort();
+            paths
+        }
+    };
+
+    let mut request_json = serde_json::to_string(request)?;
+    request_json.push('\n');
+
+    let mut responses = Vec::new();
+    for path in paths {
+        let stream = match std::os::unix::net::UnixStream::connect(&path) {
+            Ok(stream) => stream,
+            // Nothing is listening, so the socket was left behind by a bar that crashed
+            Err(e) if e.kind() == std::io::ErrorKind::ConnectionRefused => {
+                let _ = fs::remove_file(&path);
+                continue;
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+            Err(e) => {
+                responses.push((path, Response::error(format!("could not connect: {}", e))));
+                continue;
+            }
+        };
+
+        let response = exchange(stream, &request_json).unwrap_or_else(Response::error);
+        responses.push((path, response));
+    }
+
+    if responses.is_empty() {
+        return Err(match output {
+            Some(output) => color_eyre::eyre::eyre!("catfood-bar is not running on {}", output),
+            None => color_eyre::eyre::eyre!("catfood-bar is not running"),
+        });
+    }
+
+    Ok(responses)
+}
+
+/// Write one request line to a bar and read its response line
+fn exchange(
+    mut stream: std::os::unix::net::UnixStream,
+    request_json: &str,
+) -> Result<Response, String> {
+    stream
+        .set_read_timeout(Some(RESPONSE_TIMEOUT))
+        .and_then(|_| stream.set_write_timeout(Some(RESPONSE_TIMEOUT)))
+        .and_then(|_| stream.write_all(request_json.as_bytes()))
+        .map_err(|e| format!("could not send request: {}", e))?;
+
+    let mut line = String::new();
+    BufReader::new(&stream)
+        .read_line(&mut line)
+        .map_err(|e| format!("no response: {}", e))?;
+    serde_json::from_str(&line).map_err(|e| format!("invalid response: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn socket_dirs_are_made_private() {
+        let dir = std::env::temp_dir().join(format!("catfood-ipc-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::set_permissions(&dir, fs::Permissions::from_mode(0o755)).unwrap();
+
+        check_socket_dir(&dir).unwrap();
+        let mode = fs::metadata(&dir).unwrap().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn socket_dirs_must_be_directories() {
+        let path = std::env::temp_dir().join(format!("catfood-ipc-file-{}", std::process::id()));
+        fs::write(&path, "").unwrap();
+        assert!(check_socket_dir(&path).is_err());
+        let _ = fs::remove_file(&path);
+    }
+}
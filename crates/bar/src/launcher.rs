@@ -0,0 +1,216 @@
+//! Ways of starting the bar outside the current terminal.
+
+use crate::config::{Config, Launcher, PanelConfig};
+use crate::outputs;
+use clap::ValueEnum;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Find the catfood-bar executable using multiple strategies
+pub fn find_bar_executable() -> color_eyre::Result<PathBuf> {
+    // Strategy 1: Try PATH first (works for installed packages)
+    if let Ok(bar_exe) = which::which("catfood-bar") {
+        return Ok(bar_exe);
+    }
+
+    // Strategy 2: Try CARGO_BIN_EXE (works during development with cargo run)
+    if let Ok(path) = std::env::var("CARGO_BIN_EXE_catfood-bar") {
+        let path = PathBuf::from(path);
+        if path.exists() {
+            return Ok(path);
+        }
+    }
+
+    // Strategy 3: Try relative to current executable (development fallback)
+    let current_exe = std::env::current_exe()?;
+    let bar_exe = current_exe
+        .parent()
+        .unwrap_or(&current_exe)
+        .join("catfood-bar");
+
+    if bar_exe.exists() {
+        return Ok(bar_exe);
+    }
+
+    // Strategy 4: Try target directories (development fallback)
+    let current_dir = std::env::current_dir()?;
+    let target_debug = current_dir.join("target/debug/catfood-bar");
+    if target_debug.exists() {
+        return Ok(target_debug);
+    }
+
+    let target_release = current_dir.join("target/release/catfood-bar");
+    if target_release.exists() {
+        return Ok(target_release);
+    }
+
+    Err(color_eyre::eyre::eyre!(
+        "Could not find catfood-bar executable.\n\n\
+         Please install catfood-bar with one of these methods:\n\
+         • cargo install catfood-bar\n\
+         • Download from https://github.com/thombruce/catfood/releases\n\n\
+         Or ensure it's available in your PATH if already installed."
+    ))
+}
+
+/// Outputs to run a bar on: those listed under `outputs` in the config, then the panel's
+/// `output`, otherwise every connected monitor. `None` leaves the choice to the compositor.
+fn bar_outputs(profile: Option<&str>) -> Vec<Option<String>> {
+    let config = Config::load(profile, None).unwrap_or_default();
+    if !config.outputs.is_empty() {
+        return config.outputs.into_keys().map(Some).collect();
+    }
+    if let Some(output) = config.panel.output {
+        return vec![Some(output)];
+    }
+
+    match outputs::monitors() {
+        Some(monitors) if !monitors.is_empty() => monitors
+            .into_iter()
+            .map(|monitor| Some(monitor.name))
+            .collect(),
+        _ => vec![None],
+    }
+}
+
+/// The command that starts the bar for `output` with `launcher`
+fn launch_command(
+    launcher: Launcher,
+    panel: &PanelConfig,
+    bar_exe: &Path,
+    output: Option<&str>,
+) -> Command {
+    // Window title compositor rules can match to place each terminal on its output
+    let title = match output {
+        Some(output) => format!("catfood-bar {}", output),
+        None => "catfood-bar".to_string(),
+    };
+    let class = panel.class.as_deref().unwrap_or("catfood-bar");
+
+    // Spawn launchers directly with proper arguments for security
+    // This avoids shell injection risks from special characters in paths
+    let mut command = match launcher {
+        Launcher::Kitten => {
+            let mut command = Command::new("kitten");
+            command.arg("panel").args(panel.kitten_args());
+            if let Some(output) = output {
+                command.arg("--output-name").arg(output);
+            }
+            command
+        }
+        Launcher::Foot => {
+            let mut command = Command::new("foot");
+            command
+                .arg(format!("--app-id={}", class))
+                .arg(format!("--title={}", title));
+            command
+        }
+        Launcher::Alacritty => {
+            let mut command = Command::new("alacritty");
+            command
+                .arg("--class")
+                .arg(class)
+                .arg("--title")
+                .arg(&title)
+                .arg("-e");
+            command
+        }
+        Launcher::Current => unreachable!("the current terminal runs the bar directly"),
+    };
+
+    command.arg(bar_exe).arg("--no-kitten"); // Required to prevent spawning additional bars
+    if let Some(value) = launcher.to_possible_value() {
+        // Lets `restart` start the bar with the same launcher
+        command.arg("--launched-by").arg(value.get_name());
+    }
+    command
+}
+
+/// Start a bar with `launcher` on `output`, or on each output the config names, then exit
+pub fn spawn(launcher: Launcher, profile: Option<&str>, output: Option<&str>) {
+    let outputs = match output {
+        Some(output) => vec![Some(output.to_string())],
+        None => bar_outputs(profile),
+    };
+    if !launch(launcher, profile, outputs) {
+        std::process::exit(1);
+    }
+
+    // Give the bars a moment to start then exit parent
+    std::thread::sleep(std::time::Duration::from_millis(500));
+    std::process::exit(0);
+}
+
+/// Start a bar with `launcher` on each of `outputs`, reporting any failure on stderr
+/// Returns whether every bar started; if one didn't, the others are stopped again
+pub fn launch(launcher: Launcher, profile: Option<&str>, outputs: Vec<Option<String>>) -> bool {
+    // Find the bar executable using robust discovery
+    let bar_exe = match find_bar_executable() {
+        Ok(path) => path,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return false;
+        }
+    };
+
+    // Try each output's instance lock first, so a bar that is already running is reported
+    // here instead of by a panel that closes before its message can be read
+    let running: Vec<_> = outputs
+        .iter()
+        .filter_map(|output| crate::acquire_instance_lock(output.as_deref()).err())
+        .collect();
+    if !running.is_empty() {
+        for e in running {
+            eprintln!("{}", e);
+        }
+        return false;
+    }
+
+    let mut children = Vec::new();
+    let mut failures = Vec::new();
+    for output in outputs {
+        // Each output may override the panel's placement
+        let panel = Config::load(profile, output.as_deref())
+            .map(|config| config.panel)
+            .unwrap_or_default();
+
+        let mut command = launch_command(launcher, &panel, &bar_exe, output.as_deref());
+        if let Some(profile) = profile {
+            command.arg("--profile").arg(profile);
+        }
+        if let Some(output) = &output {
+            command.arg("--monitor").arg(output);
+        }
+
+        // The child processes continue running independently
+        match command.spawn() {
+            Ok(child) => children.push(child),
+            Err(e) => {
+                let program = command.get_program().to_string_lossy().into_owned();
+                failures.push((program, e));
+            }
+        }
+    }
+
+    if !failures.is_empty() {
+        for (program, e) in &failures {
+            eprintln!("Failed to launch {}: {}", program, e);
+        }
+        if launcher == Launcher::Kitten {
+            eprintln!(
+                "Make sure Kitty is installed and you're running this in a Kitty environment."
+            );
+        } else {
+            eprintln!("Make sure {} is installed and in your PATH.", failures[0].0);
+        }
+
+        // Don't leave a partial set of bars behind
+        for mut child in children {
+            let _ = child.kill();
+            let _ = child.wait();
+        }
+        return false;
+    }
+
+    true
+}
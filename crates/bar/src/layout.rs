@@ -0,0 +1,85 @@
+use crate::component_manager::ComponentManager;
+use crate::components::{BarSection, ClickRegion};
+use crate::config::{LayoutGroup, LayoutNode, SectionConstraint};
+use ratatui::{
+    Frame,
+    layout::{Alignment, Constraint, Direction, Layout, Rect},
+};
+
+/// Render a layout node and its children into `area`, collecting their click regions
+pub fn render_node(
+    frame: &mut Frame,
+    area: Rect,
+    node: &LayoutNode,
+    alignment: Alignment,
+    component_manager: &ComponentManager,
+    regions: &mut Vec<ClickRegion>,
+) {
+    match node {
+        LayoutNode::Section(name) => {
+            regions.extend(BarSection::new(name, alignment).render(frame, area, component_manager));
+        }
+        LayoutNode::Group(LayoutGroup::Horizontal(children)) => {
+            let count = children.len();
+            let constraints = children.iter().enumerate().map(|(i, child)| {
+                let outer = i == 0 || i + 1 == count;
+                node_constraint(child, component_manager, |name| {
+                    if outer {
+                        Constraint::Fill(1)
+                    } else {
+                        Constraint::Length(component_manager.get_bar_width(name))
+                    }
+                })
+            });
+            let areas = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints(constraints)
+                .split(area);
+
+            for (i, (child, area)) in children.iter().zip(areas.iter()).enumerate() {
+                // Outer sections hug the edges, inner ones sit centred
+                let alignment = if i == 0 {
+                    Alignment::Left
+                } else if i + 1 == count {
+                    Alignment::Right
+                } else {
+                    Alignment::Center
+                };
+                render_node(frame, *area, child, alignment, component_manager, regions);
+            }
+        }
+        LayoutNode::Group(LayoutGroup::Vertical(children)) => {
+            let constraints = children
+                .iter()
+                .map(|child| node_constraint(child, component_manager, |_| Constraint::Fill(1)));
+            let areas = Layout::default()
+                .direction(Direction::Vertical)
+                .constraints(constraints)
+                .split(area);
+
+            for (child, area) in children.iter().zip(areas.iter()) {
+                render_node(frame, *area, child, alignment, component_manager, regions);
+            }
+        }
+    }
+}
+
+/// Resolve a child's constraint, using `auto` to size sections left on `"auto"`
+fn node_constraint(
+    node: &LayoutNode,
+    component_manager: &ComponentManager,
+    auto: impl Fn(&str) -> Constraint,
+) -> Constraint {
+    let LayoutNode::Section(name) = node else {
+        return Constraint::Fill(1);
+    };
+
+    match component_manager.get_bar_layout(name) {
+        SectionConstraint::Auto => auto(name),
+        SectionConstraint::Min(size) => Constraint::Min(size),
+        SectionConstraint::Max(size) => Constraint::Max(size),
+        SectionConstraint::Length(size) => Constraint::Length(size),
+        SectionConstraint::Percentage(percent) => Constraint::Percentage(percent),
+        SectionConstraint::Fill(weight) => Constraint::Fill(weight),
+    }
+}
@@ -1,159 +1,390 @@
-use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
-use ratatui::{
-    DefaultTerminal, Frame,
-    layout::{Direction, Layout},
-    prelude::Constraint,
+use components::ClickRegion;
+use config::{Config, Launcher};
+use crossterm::event::{
+    DisableMouseCapture, EnableMouseCapture, Event, EventStream, KeyCode, KeyEvent, KeyEventKind,
+    KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
 };
+use futures::StreamExt;
+use ratatui::{DefaultTerminal, Frame, layout::Alignment};
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
-use std::process::Command;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 use tokio::runtime::Runtime;
 use tokio::sync::mpsc;
 
+pub mod apps;
 pub mod component_manager;
 pub mod components;
 pub mod config;
+pub mod format;
+pub mod headless;
+pub mod icons;
+pub mod ipc;
+pub mod launcher;
+pub mod layout;
 pub mod logging;
 pub mod lua_component;
+pub mod outputs;
+pub mod overflow;
+pub mod rules;
+pub mod segments;
+pub mod states;
+pub mod urgency;
 
 pub use component_manager::ComponentManager;
-pub use components::{LeftBar, MiddleBar, RightBar};
 
-/// Check if bar is already running by checking PID file
+/// Check if any bar is already running by checking PID files
 pub fn is_bar_running() -> color_eyre::Result<bool> {
-    let pid_file_path = get_pid_file_path()?;
+    for pid_file_path in get_pid_file_paths()? {
+        if read_live_pid(&pid_file_path)?.is_some() {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
 
+/// The PID in a PID file if that process is alive, removing the file if not
+fn read_live_pid(pid_file_path: &Path) -> color_eyre::Result<Option<u32>> {
     if !pid_file_path.exists() {
-        return Ok(false);
+        return Ok(None);
     }
 
-    let pid_content = fs::read_to_string(&pid_file_path)?;
+    let pid_content = fs::read_to_string(pid_file_path)?;
     let pid: u32 = pid_content
+        .lines()
+        .next()
+        .unwrap_or_default()
         .trim()
         .parse()
         .map_err(|_| color_eyre::eyre::eyre!("Invalid PID in PID file"))?;
 
-    // Check if process exists by sending signal 0
-    unsafe {
-        if libc::kill(pid as i32, 0) == 0 {
-            Ok(true) // Process exists and is alive
-        } else {
-            // Process doesn't exist, remove stale PID file
-            let _ = fs::remove_file(&pid_file_path);
-            Ok(false)
-        }
+    if is_alive(pid) {
+        Ok(Some(pid))
+    } else {
+        // Process doesn't exist, remove stale PID file
+        let _ = fs::remove_file(pid_file_path);
+        Ok(None)
     }
 }
 
-/// Find the catfood-bar executable using multiple strategies
-fn find_bar_executable() -> color_eyre::Result<std::path::PathBuf> {
-    // Strategy 1: Try PATH first (works for installed packages)
-    if let Ok(bar_exe) = which::which("catfood-bar") {
-        return Ok(bar_exe);
-    }
+/// Whether the bar with `pid` is still running
+fn is_alive(pid: u32) -> bool {
+    // Check if process exists by sending signal 0, and that it wasn't a recycled PID
+    let exists = unsafe { libc::kill(pid as i32, 0) == 0 };
+    exists && is_catfood_process(pid)
+}
 
-    // Strategy 2: Try CARGO_BIN_EXE (works during development with cargo run)
-    if let Ok(path) = std::env::var("CARGO_BIN_EXE_catfood-bar") {
-        let path = std::path::PathBuf::from(path);
-        if path.exists() {
-            return Ok(path);
-        }
+/// Whether `pid` is a catfood process (`catfood-bar` or `catfood bar`)
+/// Assumed true where `/proc` isn't available to check
+fn is_catfood_process(pid: u32) -> bool {
+    match fs::read_to_string(format!("/proc/{}/comm", pid)) {
+        Ok(comm) => comm.trim().starts_with("catfood"),
+        Err(_) => !Path::new("/proc/self").exists(),
     }
+}
 
-    // Strategy 3: Try relative to current executable (development fallback)
-    let current_exe = std::env::current_exe()?;
-    let bar_exe = current_exe
-        .parent()
-        .unwrap_or(&current_exe)
-        .join("catfood-bar");
+/// How a bar was started, saved after the PID in its PID file so `restart` can start it the
+/// same way
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct LaunchArgs {
+    /// What the bar was started in
+    pub launcher: Launcher,
+    pub profile: Option<String>,
+    pub output: Option<String>,
+}
 
-    if bar_exe.exists() {
-        return Ok(bar_exe);
+/// A running bar found through its PID file
+#[derive(Debug, Clone)]
+pub struct BarInstance {
+    /// Output the bar was started for, if any
+    pub output: Option<String>,
+    pub pid: u32,
+    /// How long ago the bar wrote its PID file
+    pub uptime: Duration,
+    /// How the bar was started, if its PID file says
+    pub launch: Option<LaunchArgs>,
+}
+
+/// Every bar that is currently running
+pub fn running_bars() -> color_eyre::Result<Vec<BarInstance>> {
+    let mut bars = Vec::new();
+    for pid_file_path in get_pid_file_paths()? {
+        let Some(pid) = read_live_pid(&pid_file_path)? else {
+            continue;
+        };
+
+        let output = pid_file_path
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.strip_prefix("bar-"))
+            .map(str::to_string);
+        let uptime = fs::metadata(&pid_file_path)?
+            .modified()?
+            .elapsed()
+            .unwrap_or_default();
+        let launch = fs::read_to_string(&pid_file_path)?
+            .lines()
+            .nth(1)
+            .and_then(|line| serde_json::from_str(line).ok());
+
+        bars.push(BarInstance {
+            output,
+            pid,
+            uptime,
+            launch,
+        });
     }
+    bars.sort_by(|a, b| a.output.cmp(&b.output));
+    Ok(bars)
+}
 
-    // Strategy 4: Try target directories (development fallback)
-    let current_dir = std::env::current_dir()?;
-    let target_debug = current_dir.join("target/debug/catfood-bar");
-    if target_debug.exists() {
-        return Ok(target_debug);
+/// Ask every running bar to quit and wait for them to exit
+/// Returns how many bars were stopped
+pub fn stop_bar() -> color_eyre::Result<usize> {
+    let bars = running_bars()?;
+    for bar in &bars {
+        // SIGTERM lets the bar restore its terminal and remove its PID file
+        unsafe {
+            libc::kill(bar.pid as i32, libc::SIGTERM);
+        }
     }
 
-    let target_release = current_dir.join("target/release/catfood-bar");
-    if target_release.exists() {
-        return Ok(target_release);
+    // Give the bars a few seconds to shut down cleanly; wait for the processes rather than
+    // their PID files, as each holds its instance lock until it exits
+    for _ in 0..30 {
+        if !bars.iter().any(|bar| is_alive(bar.pid)) {
+            return Ok(bars.len());
+        }
+        std::thread::sleep(Duration::from_millis(100));
     }
 
     Err(color_eyre::eyre::eyre!(
-        "Could not find catfood-bar executable.\n\n\
-         Please install catfood-bar with one of these methods:\n\
-         • cargo install catfood-bar\n\
-         • Download from https://github.com/thombruce/catfood/releases\n\n\
-         Or ensure it's available in your PATH if already installed."
+        "catfood-bar did not exit within 3 seconds"
     ))
 }
 
-/// Spawn bar executable in a kitten panel
-pub fn spawn_in_panel() {
-    // Find the bar executable using robust discovery
-    let bar_exe = match find_bar_executable() {
-        Ok(path) => path,
-        Err(e) => {
-            eprintln!("Error: {}", e);
-            std::process::exit(1);
-        }
-    };
+/// Stop every running bar ahead of starting them again
+/// Returns how each of them was started
+pub fn stop_for_restart() -> color_eyre::Result<Vec<LaunchArgs>> {
+    let launches = running_bars()?
+        .into_iter()
+        .map(|bar| {
+            bar.launch.unwrap_or(LaunchArgs {
+                output: bar.output,
+                ..LaunchArgs::default()
+            })
+        })
+        .collect();
+    stop_bar()?;
+    Ok(launches)
+}
 
-    // Spawn kitten panel directly with proper arguments for security
-    // This avoids shell injection risks from special characters in paths
-    match Command::new("kitten")
-        .arg("panel")
-        .arg(&bar_exe)
-        .arg("--no-kitten") // Required to prevent spawning additional panels
-        .spawn()
-    {
-        Ok(_child) => {
-            // Give panel a moment to start then exit parent
-            // The child process continues running independently
-            std::thread::sleep(std::time::Duration::from_millis(500));
-            std::process::exit(0);
+/// Start the bars stopped by [`stop_for_restart`] again the way each was started, with
+/// options given on the command line taking precedence
+/// Bars from a launcher are started through it again. The first bar that ran in a plain
+/// terminal is returned to be run in this one; any others can't be, as their terminals are gone
+pub fn restart_bars(
+    previous: Vec<LaunchArgs>,
+    no_kitten: bool,
+    launcher: Option<Launcher>,
+    profile: Option<&str>,
+    output: Option<&str>,
+) -> color_eyre::Result<Option<LaunchArgs>> {
+    let mut bars: Vec<LaunchArgs> = Vec::new();
+    for bar in previous {
+        let bar = LaunchArgs {
+            launcher: match launcher {
+                _ if no_kitten => Launcher::Current,
+                Some(launcher) => launcher,
+                None => bar.launcher,
+            },
+            profile: profile.map(str::to_string).or(bar.profile),
+            output: output.map(str::to_string).or(bar.output),
+        };
+        // An explicit --monitor can leave several bars launched the same way
+        if !bars.contains(&bar) {
+            bars.push(bar);
         }
-        Err(e) => {
-            eprintln!("Failed to spawn kitten panel: {}", e);
-            eprintln!(
-                "Make sure Kitty is installed and you're running this in a Kitty environment."
-            );
-            std::process::exit(1);
+    }
+
+    let (here, elsewhere): (Vec<_>, Vec<_>) = bars
+        .into_iter()
+        .partition(|bar| bar.launcher == Launcher::Current);
+    let failed = elsewhere
+        .iter()
+        .filter(|bar| {
+            !launcher::launch(
+                bar.launcher,
+                bar.profile.as_deref(),
+                vec![bar.output.clone()],
+            )
+        })
+        .count();
+
+    let mut here = here.into_iter();
+    let first = here.next();
+    for bar in here {
+        eprintln!(
+            "catfood-bar on {} ran in a terminal of its own; start it there again with --no-kitten",
+            bar.output.as_deref().unwrap_or("the default output")
+        );
+    }
+
+    if failed > 0 {
+        return Err(color_eyre::eyre::eyre!(
+            "{} of {} bars failed to restart",
+            failed,
+            elsewhere.len()
+        ));
+    }
+    if first.is_none() {
+        // Give the bars a moment to start before exiting
+        std::thread::sleep(Duration::from_millis(500));
+    }
+    Ok(first)
+}
+
+/// Print whether the bar is running, with details of each instance
+pub fn print_status() -> color_eyre::Result<()> {
+    let bars = running_bars()?;
+    let mut stdout = std::io::stdout();
+
+    if bars.is_empty() {
+        writeln!(stdout, "catfood-bar is not running")?;
+    }
+    for bar in &bars {
+        let output = bar.output.as_deref().unwrap_or("default output");
+        writeln!(
+            stdout,
+            "catfood-bar is running on {} (PID {}, up {}, {})",
+            output,
+            bar.pid,
+            format_uptime(bar.uptime),
+            running_components(bar.output.as_deref())
+        )?;
+    }
+
+    writeln!(stdout, "Config: {}", Config::config_path().display())?;
+    Ok(())
+}
+
+/// How many components the bar on `output` is running, asked over its control socket
+fn running_components(output: Option<&str>) -> String {
+    let response = ipc::send(&ipc::Request::DumpState, output)
+        .ok()
+        .and_then(|responses| responses.into_iter().next())
+        .map(|(_, response)| response);
+    match response.and_then(|response| response.data) {
+        Some(state) => {
+            let count = state["components"].as_array().map_or(0, Vec::len);
+            format!("{} components", count)
         }
+        None => "components unknown".to_string(),
+    }
+}
+
+/// Format a duration as e.g. `2h 5m` or `40s`
+fn format_uptime(uptime: Duration) -> String {
+    let secs = uptime.as_secs();
+    let (days, hours, minutes) = (secs / 86400, secs / 3600 % 24, secs / 60 % 60);
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs % 60)
+    } else {
+        format!("{}s", secs)
     }
 }
 
-/// Handle common bar CLI logic: check if running and optionally spawn in panel
-/// Returns true if spawning in panel (process will exit via spawn_in_panel),
+/// Handle common bar CLI logic: check if running and optionally launch the bar elsewhere
+/// Returns true if launching (process will exit via launcher::spawn),
 /// false if should continue with direct execution
-pub fn handle_bar_cli(no_kitten: bool) -> bool {
-    if !no_kitten {
-        // Check if already running
-        if let Ok(true) = is_bar_running() {
-            eprintln!("catfood-bar is already running");
-            std::process::exit(1);
+pub fn handle_bar_cli(
+    no_kitten: bool,
+    launcher: Option<Launcher>,
+    profile: Option<&str>,
+    output: Option<&str>,
+) -> bool {
+    let launcher = if no_kitten {
+        Launcher::Current
+    } else {
+        launcher.unwrap_or_else(|| {
+            Config::load(profile, output)
+                .map(|config| config.panel.launcher)
+                .unwrap_or_default()
+        })
+    };
+
+    if launcher == Launcher::Current {
+        return false; // Continue with direct execution (--no-kitten case)
+    }
+
+    // Launch the bar - this function will exit the process
+    launcher::spawn(launcher, profile, output);
+    // This line is unreachable, but required for type compatibility
+    unreachable!("launcher::spawn() should have exited the process")
+}
+
+/// Send a command to running bars over their control sockets, printing any replies
+pub fn send_message(request: &ipc::Request, output: Option<&str>) -> color_eyre::Result<()> {
+    let responses = ipc::send(request, output)?;
+    let total = responses.len();
+    let mut failed = 0;
+    for (path, response) in responses {
+        if let Some(error) = response.error {
+            eprintln!("{}: {}", path.display(), error);
+            failed += 1;
+        }
+        if let Some(data) = response.data {
+            writeln!(
+                std::io::stdout(),
+                "{}",
+                serde_json::to_string_pretty(&data)?
+            )?;
         }
+    }
 
-        // Spawn in panel - this function will exit the process
-        spawn_in_panel();
-        // This line is unreachable, but required for type compatibility
-        unreachable!("spawn_in_panel() should have exited the process")
-    } else {
-        false // Continue with direct execution (--no-kitten case)
+    if failed > 0 {
+        return Err(color_eyre::eyre::eyre!(
+            "{} of {} bars reported an error",
+            failed,
+            total
+        ));
     }
+    Ok(())
 }
 
-pub fn run_bar() -> color_eyre::Result<()> {
+/// Print the JSON Schema for `bar.json` to stdout
+pub fn print_schema() -> color_eyre::Result<()> {
+    let schema = serde_json::to_string_pretty(&config::Config::json_schema())?;
+    writeln!(std::io::stdout(), "{}", schema)?;
+    Ok(())
+}
+
+/// Run the bar in the current terminal; `launched_by` records what that terminal was started
+/// in, for `restart`
+pub fn run_bar(
+    profile: Option<&str>,
+    output: Option<&str>,
+    launched_by: Launcher,
+) -> color_eyre::Result<()> {
     color_eyre::install()?;
 
+    // Hold the instance lock for as long as the bar runs
+    let _lock = acquire_instance_lock(output).inspect_err(|e| {
+        logging::log_system_error("Instance Lock", &format!("{}", e));
+    })?;
+
     // Create PID file at bar startup (not in parent)
-    if let Err(e) = create_pid_file() {
+    let launch = LaunchArgs {
+        launcher: launched_by,
+        profile: profile.map(str::to_string),
+        output: output.map(str::to_string),
+    };
+    if let Err(e) = create_pid_file(&launch) {
         eprintln!("Failed to create PID file: {}", e);
         return Err(e);
     }
@@ -163,13 +394,16 @@ pub fn run_bar() -> color_eyre::Result<()> {
 
     let result = rt.block_on(async {
         let terminal = ratatui::init();
-        let app_result = App::new()?.run_async(terminal).await;
+        let _ = crossterm::execute!(std::io::stdout(), EnableMouseCapture);
+        let app_result = App::new(profile, output)?.run_async(terminal).await;
+        let _ = crossterm::execute!(std::io::stdout(), DisableMouseCapture);
         ratatui::restore();
         app_result
     });
 
-    // Clean up PID file on exit
-    let _ = remove_pid_file();
+    // Clean up PID file and control socket on exit
+    let _ = remove_pid_file(output);
+    ipc::remove_socket(output);
 
     result
 }
@@ -180,28 +414,39 @@ pub struct App {
     /// Is the application running?
     running: bool,
     component_manager: ComponentManager,
-    left_bar: LeftBar,
-    middle_bar: MiddleBar,
-    right_bar: RightBar,
+    /// Where each component was drawn in the last frame
+    click_regions: Vec<ClickRegion>,
     reload_rx: mpsc::Receiver<()>,
+    /// Commands received on the control socket
+    ipc_rx: mpsc::Receiver<ipc::PendingRequest>,
+    /// Hidden bars draw nothing until shown again
+    hidden: bool,
 }
 
 impl App {
     /// Construct a new instance of [`App`].
-    pub fn new() -> color_eyre::Result<Self> {
-        let component_manager = ComponentManager::new()?;
+    pub fn new(profile: Option<&str>, output: Option<&str>) -> color_eyre::Result<Self> {
+        let component_manager = ComponentManager::new(profile, output)?;
         let (reload_tx, reload_rx) = mpsc::channel(10);
 
         // Start file watcher
         Self::start_config_watcher(reload_tx)?;
 
+        // Start control socket
+        let (ipc_tx, ipc_rx) = mpsc::channel(10);
+        // The bar still runs without its control socket, e.g. when the socket directory is
+        // someone else's
+        if let Err(e) = ipc::serve(output, ipc_tx) {
+            logging::log_system_error("Control Socket", &format!("{}", e));
+        }
+
         Ok(Self {
             running: true,
             component_manager,
-            left_bar: LeftBar::new()?,
-            middle_bar: MiddleBar::new()?,
-            right_bar: RightBar::new()?,
+            click_regions: Vec::new(),
             reload_rx,
+            ipc_rx,
+            hidden: false,
         })
     }
 
@@ -252,9 +497,10 @@ impl App {
             while let Some(event) = rx.recv().await {
                 use notify::EventKind;
 
-                // Check if the event is related to our config file
+                // Check if the event is related to our config file or a JSON fragment beside it
                 if let Some(path) = event.paths.first()
-                    && path == &config_path
+                    && (path == &config_path
+                        || path.extension().and_then(|ext| ext.to_str()) == Some("json"))
                     && matches!(event.kind, EventKind::Modify(_) | EventKind::Create(_))
                     && let Err(e) = reload_tx.send(()).await
                 {
@@ -272,70 +518,130 @@ impl App {
 
     /// Run the application's main loop.
     pub async fn run_async(mut self, mut terminal: DefaultTerminal) -> color_eyre::Result<()> {
+        use tokio::signal::unix::{SignalKind, signal};
+
+        // Quit cleanly when asked to, so the terminal is restored and the PID file removed
+        let mut sigterm = signal(SignalKind::terminate())?;
+        let mut sigint = signal(SignalKind::interrupt())?;
+        let mut sighup = signal(SignalKind::hangup())?;
+
+        // Terminal input arrives on its own branch so it never delays updates or requests
+        let mut events = EventStream::new();
+        let mut tick = tokio::time::interval(Duration::from_millis(333));
+        tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+
         while self.running {
             tokio::select! {
+                _ = sigterm.recv() => self.quit(),
+                _ = sigint.recv() => self.quit(),
+                _ = sighup.recv() => self.quit(),
                 _ = self.reload_rx.recv() => {
                     // Handle config reload
                     if let Err(e) = self.component_manager.reload() {
                         logging::log_config_error(&format!("Failed to reload configuration: {}", e));
                     }
                 }
-                _ = tokio::time::sleep(Duration::from_millis(333)) => {
+                Some((request, reply_tx)) = self.ipc_rx.recv() => {
+                    let _ = reply_tx.send(self.handle_request(request));
+                    terminal.draw(|frame| self.render(frame))?;
+                }
+                Some(event) = events.next() => {
+                    self.handle_crossterm_event(event?);
+                    terminal.draw(|frame| self.render(frame))?;
+                }
+                _ = tick.tick() => {
                     // Normal update cycle
                     self.update_components();
                     terminal.draw(|frame| self.render(frame))?;
-                    self.handle_crossterm_events()?;
                 }
             }
         }
         Ok(())
     }
 
+    /// Answers a command received on the control socket
+    fn handle_request(&mut self, request: ipc::Request) -> ipc::Response {
+        match request {
+            ipc::Request::Reload => match self.component_manager.reload() {
+                Ok(()) => ipc::Response::ok(None),
+                Err(e) => ipc::Response::error(format!("Failed to reload configuration: {}", e)),
+            },
+            ipc::Request::ToggleVisibility => {
+                self.hidden = !self.hidden;
+                ipc::Response::ok(Some(serde_json::json!({ "visible": !self.hidden })))
+            }
+            ipc::Request::SetColorize { value } => {
+                let colorize = self.component_manager.set_colorize(value);
+                ipc::Response::ok(Some(serde_json::json!({ "colorize": colorize })))
+            }
+            ipc::Request::Refresh { component } => match self.component_manager.refresh(&component)
+            {
+                Ok(0) => ipc::Response::error(format!("No component named {}", component)),
+                Ok(count) => ipc::Response::ok(Some(serde_json::json!({ "refreshed": count }))),
+                Err(e) => ipc::Response::error(format!("Failed to refresh {}: {}", component, e)),
+            },
+            ipc::Request::Set {
+                id,
+                text,
+                color,
+                tooltip,
+                expire,
+            } => {
+                if let Some(color) = &color
+                    && config::parse_color(color).is_none()
+                {
+                    return ipc::Response::error(format!("Invalid color: {}", color));
+                }
+                let matched = self.component_manager.text_components(&id);
+                if matched == 0 {
+                    return ipc::Response::error(format!("No text component with id {}", id));
+                }
+                components::text::set(&id, text, color, tooltip, expire.map(Duration::from_secs));
+                self.update_components();
+                ipc::Response::ok(Some(serde_json::json!({ "matched": matched })))
+            }
+            ipc::Request::DumpState => {
+                let mut state = self.component_manager.dump_state();
+                state["visible"] = (!self.hidden).into();
+                ipc::Response::ok(Some(state))
+            }
+        }
+    }
+
     fn update_components(&mut self) {
         if let Err(e) = self.component_manager.update() {
             logging::log_system_error("Component Manager", &format!("{}", e));
         }
-        if let Err(e) = self.left_bar.update() {
-            logging::log_system_error("Left Bar", &format!("{}", e));
-        }
-        if let Err(e) = self.middle_bar.update() {
-            logging::log_system_error("Middle Bar", &format!("{}", e));
-        }
-        if let Err(e) = self.right_bar.update() {
-            logging::log_system_error("Right Bar", &format!("{}", e));
-        }
     }
 
     /// Renders the user interface.
     fn render(&mut self, frame: &mut Frame) {
-        let layout = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-                Constraint::Ratio(1, 3),
-            ])
-            .split(frame.area());
-
-        self.left_bar
-            .render(frame, layout[0], &self.component_manager);
-        self.middle_bar
-            .render(frame, layout[1], &self.component_manager);
-        self.right_bar
-            .render(frame, layout[2], &self.component_manager);
-    }
-
-    /// Reads the crossterm events and updates the state of [`App`].
-    fn handle_crossterm_events(&mut self) -> color_eyre::Result<()> {
-        if event::poll(Duration::from_millis(333))? {
-            match event::read()? {
-                Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
-                Event::Mouse(_) => {}
-                Event::Resize(_, _) => {}
-                _ => {}
-            }
+        if self.hidden {
+            self.click_regions.clear();
+            return;
+        }
+
+        let root = &self.component_manager.get_layout().root;
+        let mut click_regions = Vec::new();
+        layout::render_node(
+            frame,
+            frame.area(),
+            root,
+            Alignment::Left,
+            &self.component_manager,
+            &mut click_regions,
+        );
+        self.click_regions = click_regions;
+    }
+
+    /// Updates the state of [`App`] for a crossterm event.
+    fn handle_crossterm_event(&mut self, event: Event) {
+        match event {
+            Event::Key(key) if key.kind == KeyEventKind::Press => self.on_key_event(key),
+            Event::Mouse(mouse) => self.on_mouse_event(mouse),
+            Event::Resize(_, _) => {}
+            _ => {}
         }
-        Ok(())
     }
 
     /// Handles the key events and updates the state of [`App`].
@@ -347,14 +653,30 @@ impl App {
         }
     }
 
+    /// Handles mouse events, toggling alternate formats on click.
+    fn on_mouse_event(&mut self, mouse: MouseEvent) {
+        if mouse.kind != MouseEventKind::Down(MouseButton::Left) {
+            return;
+        }
+
+        let position = ratatui::layout::Position::new(mouse.column, mouse.row);
+        if let Some(region) = self
+            .click_regions
+            .iter()
+            .find(|region| region.area.contains(position))
+        {
+            self.component_manager.on_click(&region.key);
+        }
+    }
+
     /// Set running to false to quit the application.
     fn quit(&mut self) {
         self.running = false;
     }
 }
 
-/// Get the PID file path (same as in catfood crate)
-fn get_pid_file_path() -> color_eyre::Result<PathBuf> {
+/// Directory holding the bar's PID files
+fn get_pid_dir() -> color_eyre::Result<PathBuf> {
     let data_dir = std::env::var("XDG_DATA_HOME").unwrap_or_else(|_| {
         let home = std::env::var("HOME").unwrap_or_else(|_| ".".to_string());
         format!("{}/.local/share", home)
@@ -363,12 +685,70 @@ fn get_pid_file_path() -> color_eyre::Result<PathBuf> {
     let catfood_dir = PathBuf::from(data_dir).join("catfood");
     fs::create_dir_all(&catfood_dir)?;
 
-    Ok(catfood_dir.join("bar.pid"))
+    Ok(catfood_dir)
+}
+
+/// Get the PID file path for the bar on `output` (same as in catfood crate)
+fn get_pid_file_path(output: Option<&str>) -> color_eyre::Result<PathBuf> {
+    let file_name = match output {
+        Some(output) => format!("bar-{}.pid", output),
+        None => "bar.pid".to_string(),
+    };
+    Ok(get_pid_dir()?.join(file_name))
+}
+
+/// Paths of every bar PID file, one per running instance
+fn get_pid_file_paths() -> color_eyre::Result<Vec<PathBuf>> {
+    let mut paths = Vec::new();
+    for entry in fs::read_dir(get_pid_dir()?)? {
+        let path = entry?.path();
+        let is_bar_pid = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| name.starts_with("bar") && name.ends_with(".pid"));
+        if is_bar_pid {
+            paths.push(path);
+        }
+    }
+    Ok(paths)
+}
+
+/// Take the exclusive lock for the bar on `output`, failing if another instance holds it
+/// The lock is released when the returned file is dropped or the process exits
+pub(crate) fn acquire_instance_lock(output: Option<&str>) -> color_eyre::Result<fs::File> {
+    use std::os::fd::AsRawFd;
+
+    let lock_path = get_pid_file_path(output)?.with_extension("lock");
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(&lock_path)?;
+
+    if unsafe { libc::flock(file.as_raw_fd(), libc::LOCK_EX | libc::LOCK_NB) } != 0 {
+        let error = std::io::Error::last_os_error();
+        if error.kind() != std::io::ErrorKind::WouldBlock {
+            return Err(error.into());
+        }
+
+        let holder = read_live_pid(&get_pid_file_path(output)?)
+            .ok()
+            .flatten()
+            .map(|pid| format!(" (PID {})", pid))
+            .unwrap_or_default();
+        return Err(color_eyre::eyre::eyre!(
+            "catfood-bar is already running on {}{}",
+            output.unwrap_or("this output"),
+            holder
+        ));
+    }
+
+    Ok(file)
 }
 
 /// Remove PID file
-fn remove_pid_file() -> color_eyre::Result<()> {
-    let pid_file_path = get_pid_file_path()?;
+fn remove_pid_file(output: Option<&str>) -> color_eyre::Result<()> {
+    let pid_file_path = get_pid_file_path(output)?;
 
     if pid_file_path.exists() {
         fs::remove_file(&pid_file_path)?;
@@ -377,13 +757,14 @@ fn remove_pid_file() -> color_eyre::Result<()> {
     Ok(())
 }
 
-/// Create PID file with current process ID
-fn create_pid_file() -> color_eyre::Result<()> {
-    let pid_file_path = get_pid_file_path()?;
+/// Create PID file with current process ID, followed by how the bar was started
+fn create_pid_file(launch: &LaunchArgs) -> color_eyre::Result<()> {
+    let pid_file_path = get_pid_file_path(launch.output.as_deref())?;
     let pid = std::process::id();
 
     let mut file = fs::File::create(&pid_file_path)?;
     writeln!(file, "{}", pid)?;
+    writeln!(file, "{}", serde_json::to_string(launch)?)?;
 
     Ok(())
 }
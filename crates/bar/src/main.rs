@@ -1,25 +1,126 @@
-use catfood_bar::{handle_bar_cli, run_bar};
-use clap::Parser;
+use catfood_bar::config::Launcher;
+use catfood_bar::headless::{self, OutputFormat};
+use catfood_bar::{
+    handle_bar_cli, print_schema, print_status, restart_bars, run_bar, stop_bar, stop_for_restart,
+};
+use clap::{Parser, Subcommand};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "catfood-bar")]
 #[command(about = "A system bar component of the catfood utility suite")]
 struct Cli {
+    #[command(subcommand)]
+    command: Option<Commands>,
+
     /// Run without spawning in a kitten panel
     #[arg(long = "no-kitten")]
     no_kitten: bool,
+
+    /// What to start the bar in, overriding the config's `panel.launcher`
+    #[arg(long, value_enum)]
+    launcher: Option<Launcher>,
+
+    /// What the terminal running the bar was started in, set by the launcher for `restart`
+    #[arg(long, value_enum, hide = true)]
+    launched_by: Option<Launcher>,
+
+    /// Config profile to apply (defaults to one matching the hostname)
+    #[arg(long)]
+    profile: Option<String>,
+
+    /// Monitor this bar runs on, selecting its `outputs` overrides
+    #[arg(long = "monitor", value_name = "MONITOR")]
+    output: Option<String>,
+
+    /// Write to stdout in this format instead of drawing into a terminal
+    #[arg(long = "output", visible_alias = "format", value_enum)]
+    format: Option<OutputFormat>,
+
+    /// Update every component once, write a single status line and exit (defaults to text)
+    #[arg(long)]
+    once: bool,
+
+    /// Seconds between status lines written with --output
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+    interval: u64,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Print the JSON Schema for bar.json
+    Schema,
+    /// Stop every running bar
+    Stop,
+    /// Stop every running bar and start each again
+    Restart,
+    /// Show whether the bar is running
+    Status,
 }
 
 fn main() -> color_eyre::Result<()> {
     let cli = Cli::parse();
 
+    match cli.command {
+        Some(Commands::Schema) => return print_schema(),
+        Some(Commands::Stop) => {
+            if stop_bar()? == 0 {
+                println!("catfood-bar is not running");
+            }
+            return Ok(());
+        }
+        Some(Commands::Status) => return print_status(),
+        Some(Commands::Restart) => {
+            // Start each bar the way it was started before, unless told otherwise; with none
+            // running, start the bar as usual
+            let previous = stop_for_restart()?;
+            if !previous.is_empty() {
+                let restarted = restart_bars(
+                    previous,
+                    cli.no_kitten,
+                    cli.launcher,
+                    cli.profile.as_deref(),
+                    cli.output.as_deref(),
+                )?;
+                return match restarted {
+                    Some(here) => run_bar(
+                        here.profile.as_deref(),
+                        here.output.as_deref(),
+                        Launcher::Current,
+                    ),
+                    None => Ok(()),
+                };
+            }
+        }
+        None => {}
+    }
+
+    if cli.format.is_some() || cli.once {
+        return headless::run(
+            cli.format.unwrap_or(OutputFormat::Text),
+            cli.once,
+            Duration::from_secs(cli.interval),
+            cli.profile.as_deref(),
+            cli.output.as_deref(),
+        );
+    }
+
     // Handle common CLI logic
-    if handle_bar_cli(cli.no_kitten) {
+    if handle_bar_cli(
+        cli.no_kitten,
+        cli.launcher,
+        cli.profile.as_deref(),
+        cli.output.as_deref(),
+    ) {
         // This return is unreachable - handle_bar_cli spawns panel and exits process
         // Required for type compatibility since handle_bar_cli returns bool
         return Ok(());
     }
 
     // Run directly with existing behavior (--no-kitten case)
-    run_bar()
+    run_bar(
+        cli.profile.as_deref(),
+        cli.output.as_deref(),
+        cli.launched_by.unwrap_or(Launcher::Current),
+    )
 }
@@ -0,0 +1,45 @@
+//! Monitors known to Hyprland, and which one this bar instance is drawn on.
+
+use crate::logging;
+use serde::Deserialize;
+use std::process::Command;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Monitor {
+    pub id: i32,
+    pub name: String,
+    pub focused: bool,
+    pub active_workspace: ActiveWorkspace,
+}
+
+#[derive(Deserialize, Debug, Clone)]
+pub struct ActiveWorkspace {
+    pub id: i32,
+}
+
+/// Every connected monitor
+pub fn monitors() -> Option<Vec<Monitor>> {
+    let output = Command::new("hyprctl")
+        .args(["monitors", "-j"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        logging::log_system_error(
+            "outputs",
+            str::from_utf8(&output.stderr).unwrap_or("unknown error"),
+        );
+        return None;
+    }
+
+    serde_json::from_slice(&output.stdout).ok()
+}
+
+/// The monitor a bar started for `output` is drawn on: that output, otherwise whichever is
+/// focused
+pub fn bar_monitor(output: Option<&str>) -> Option<Monitor> {
+    monitors()?.into_iter().find(|monitor| match output {
+        Some(name) => monitor.name == name,
+        None => monitor.focused,
+    })
+}
@@ -0,0 +1,228 @@
+use ratatui::{style::Color, text::Span};
+use std::cmp::Reverse;
+use unicode_width::UnicodeWidthChar;
+
+const ELLIPSIS: &str = "…";
+
+/// One component's rendered spans along with its sizing preferences
+#[derive(Debug)]
+pub struct Segment<'a> {
+    /// Key of the component or segment group the spans came from
+    pub key: String,
+    pub spans: Vec<Span<'a>>,
+    pub priority: i32,
+    pub min_width: Option<u16>,
+    pub max_width: Option<u16>,
+    /// Background shared by the whole segment, when it is a styled segment group
+    pub background: Option<Color>,
+}
+
+impl Segment<'_> {
+    fn width(&self) -> usize {
+        self.spans.iter().map(|span| span.width()).sum()
+    }
+}
+
+/// Fit segments into `width` cells, returning the ones still visible.
+///
+/// Segments are first capped at their `max_width`. If the total still overflows,
+/// segments with a `min_width` are ellipsized down towards it, and then whole
+/// segments are hidden, lowest priority first (later segments first on ties).
+pub fn fit_segments(mut segments: Vec<Segment<'_>>, width: u16) -> Vec<Segment<'_>> {
+    for segment in &mut segments {
+        if let Some(max_width) = segment.max_width {
+            truncate_spans(&mut segment.spans, max_width as usize);
+        }
+    }
+
+    let available = width as usize;
+    let mut total: usize = segments.iter().map(Segment::width).sum();
+
+    let mut order: Vec<usize> = (0..segments.len()).collect();
+    order.sort_by_key(|&i| (segments[i].priority, Reverse(i)));
+
+    for &i in &order {
+        if total <= available {
+            break;
+        }
+        let segment = &mut segments[i];
+        let current = segment.width();
+        if let Some(min_width) = segment.min_width.map(|w| w as usize)
+            && current > min_width
+        {
+            let target = min_width.max(current.saturating_sub(total - available));
+            truncate_spans(&mut segment.spans, target);
+            total = total - current + segment.width();
+        }
+    }
+
+    let mut visible = vec![true; segments.len()];
+    for &i in &order {
+        if total <= available {
+            break;
+        }
+        total -= segments[i].width();
+        visible[i] = false;
+    }
+
+    segments
+        .into_iter()
+        .zip(visible)
+        .filter(|(_, visible)| *visible)
+        .map(|(segment, _)| segment)
+        .collect()
+}
+
+/// Shorten spans to at most `max_width` cells, ending in an ellipsis when cut
+pub fn truncate_spans(spans: &mut Vec<Span<'_>>, max_width: usize) {
+    let width: usize = spans.iter().map(|span| span.width()).sum();
+    if width <= max_width {
+        return;
+    }
+    if max_width == 0 {
+        spans.clear();
+        return;
+    }
+
+    let mut remaining = max_width - 1;
+    let mut truncated = Vec::new();
+    for span in spans.drain(..) {
+        let mut content = String::new();
+        for c in span.content.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if char_width > remaining {
+                break;
+            }
+            remaining -= char_width;
+            content.push(c);
+        }
+
+        let cut = content.len() < span.content.len();
+        let style = span.style;
+        if !content.is_empty() {
+            truncated.push(Span::styled(content, style));
+        }
+        if cut {
+            truncated.push(Span::styled(ELLIPSIS, style));
+            break;
+        }
+    }
+    *spans = truncated;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ratatui::style::Stylize;
+
+    fn segment(key: &str, text: &'static str, priority: i32) -> Segment<'static> {
+        Segment {
+            key: key.to_string(),
+            spans: vec![Span::raw(text)],
+            priority,
+            min_width: None,
+            max_width: None,
+            background: None,
+        }
+    }
+
+    fn keys(segments: &[Segment]) -> Vec<String> {
+        segments.iter().map(|segment| segment.key.clone()).collect()
+    }
+
+    fn text(spans: &[Span]) -> String {
+        spans.iter().map(|span| span.content.as_ref()).collect()
+    }
+
+    #[test]
+    fn everything_fits() {
+        let segments = vec![segment("a", "aaa", 0), segment("b", "bbb", 0)];
+        assert_eq!(keys(&fit_segments(segments, 6)), ["a", "b"]);
+    }
+
+    #[test]
+    fn lowest_priority_is_dropped_first() {
+        let segments = vec![
+            segment("a", "aaa", 5),
+            segment("b", "bbb", 1),
+            segment("c", "ccc", 3),
+        ];
+        assert_eq!(keys(&fit_segments(segments, 6)), ["a", "c"]);
+    }
+
+    #[test]
+    fn later_segments_are_dropped_first_on_ties() {
+        let segments = vec![
+            segment("a", "aaa", 0),
+            segment("b", "bbb", 0),
+            segment("c", "ccc", 0),
+        ];
+        assert_eq!(keys(&fit_segments(segments, 7)), ["a", "b"]);
+        let segments = vec![
+            segment("a", "aaa", 0),
+            segment("b", "bbb", 0),
+            segment("c", "ccc", 0),
+        ];
+        assert_eq!(keys(&fit_segments(segments, 3)), ["a"]);
+    }
+
+    #[test]
+    fn min_width_segments_shrink_before_anything_is_dropped() {
+        let mut title = segment("title", "a long window title", 0);
+        title.min_width = Some(5);
+        let segments = vec![segment("clock", "12:00", 1), title];
+
+        let fitted = fit_segments(segments, 15);
+        assert_eq!(keys(&fitted), ["clock", "title"]);
+        assert_eq!(text(&fitted[1].spans), "a long wi…");
+        assert_eq!(fitted[1].width(), 10);
+    }
+
+    #[test]
+    fn max_width_caps_segments_even_when_they_fit() {
+        let mut title = segment("title", "abcdefgh", 0);
+        title.max_width = Some(4);
+        let fitted = fit_segments(vec![title], 80);
+        assert_eq!(text(&fitted[0].spans), "abc…");
+    }
+
+    #[test]
+    fn truncation_keeps_span_styles() {
+        let mut spans = vec![Span::raw("ab").red(), Span::raw("cdef").blue()];
+        truncate_spans(&mut spans, 4);
+        assert_eq!(
+            spans,
+            [
+                Span::raw("ab").red(),
+                Span::raw("c").blue(),
+                Span::raw("…").blue()
+            ]
+        );
+    }
+
+    #[test]
+    fn truncation_never_splits_a_wide_character() {
+        // Each character is two cells wide
+        let mut spans = vec![Span::raw("日本語")];
+        truncate_spans(&mut spans, 4);
+        assert_eq!(text(&spans), "日…");
+
+        let mut spans = vec![Span::raw("日本語")];
+        truncate_spans(&mut spans, 5);
+        assert_eq!(text(&spans), "日本…");
+
+        let mut spans = vec![Span::raw("日本語")];
+        truncate_spans(&mut spans, 1);
+        assert_eq!(text(&spans), "…");
+    }
+
+    #[test]
+    fn truncation_to_zero_or_exact_width() {
+        let mut spans = vec![Span::raw("日本")];
+        truncate_spans(&mut spans, 4);
+        assert_eq!(text(&spans), "日本");
+
+        truncate_spans(&mut spans, 0);
+        assert!(spans.is_empty());
+    }
+}
@@ -0,0 +1,297 @@
+//! A small expression language for `show_when` / `hide_when`.
+//!
+//! Rules compare a component's values against literals, e.g.
+//! `state == full`, `percent >= 95 && !charging` or `sink != "alsa_output.usb"`.
+//! `&&` binds tighter than `||`, parentheses group, and a bare name is true
+//! unless its value is empty, `false` or `0`.
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Str(String),
+    Op(&'static str),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+}
+
+#[derive(Debug)]
+enum Expr {
+    Truthy(String),
+    Compare(String, &'static str, String),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+/// A parsed rule, ready to evaluate on every frame
+#[derive(Debug)]
+pub struct Rule(Expr);
+
+impl Rule {
+    pub fn parse(rule: &str) -> Result<Self, String> {
+        parse(rule).map(Self)
+    }
+
+    /// Evaluate the rule against a component's values
+    pub fn evaluate(&self, values: &[(&str, String)]) -> bool {
+        eval(&self.0, values)
+    }
+}
+
+fn tokenize(rule: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = rule.chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RParen);
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut literal = String::new();
+                loop {
+                    match chars.next() {
+                        Some(end) if end == c => break,
+                        Some(ch) => literal.push(ch),
+                        None => return Err(format!("unterminated string in `{}`", rule)),
+                    }
+                }
+                tokens.push(Token::Str(literal));
+            }
+            '&' | '|' => {
+                chars.next();
+                if chars.next() != Some(c) {
+                    return Err(format!("expected `{}{}` in `{}`", c, c, rule));
+                }
+                tokens.push(if c == '&' { Token::And } else { Token::Or });
+            }
+            '=' | '!' | '<' | '>' => {
+                chars.next();
+                let followed_by_eq = chars.peek() == Some(&'=');
+                if followed_by_eq {
+                    chars.next();
+                }
+                tokens.push(match (c, followed_by_eq) {
+                    ('=', true) => Token::Op("=="),
+                    ('!', true) => Token::Op("!="),
+                    ('<', true) => Token::Op("<="),
+                    ('>', true) => Token::Op(">="),
+                    ('<', false) => Token::Op("<"),
+                    ('>', false) => Token::Op(">"),
+                    ('!', false) => Token::Not,
+                    _ => return Err(format!("expected `==` in `{}`", rule)),
+                });
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&ch) = chars.peek() {
+                    if ch.is_whitespace() || "()\"'&|=!<>".contains(ch) {
+                        break;
+                    }
+                    word.push(ch);
+                    chars.next();
+                }
+                tokens.push(Token::Word(word));
+            }
+        }
+    }
+
+    Ok(tokens)
+}
+
+fn parse(rule: &str) -> Result<Expr, String> {
+    let tokens = tokenize(rule)?;
+    let mut position = 0;
+    let expr = parse_or(&tokens, &mut position, rule)?;
+    if position != tokens.len() {
+        return Err(format!("unexpected input in `{}`", rule));
+    }
+    Ok(expr)
+}
+
+fn parse_or(tokens: &[Token], position: &mut usize, rule: &str) -> Result<Expr, String> {
+    let mut expr = parse_and(tokens, position, rule)?;
+    while tokens.get(*position) == Some(&Token::Or) {
+        *position += 1;
+        expr = Expr::Or(Box::new(expr), Box::new(parse_and(tokens, position, rule)?));
+    }
+    Ok(expr)
+}
+
+fn parse_and(tokens: &[Token], position: &mut usize, rule: &str) -> Result<Expr, String> {
+    let mut expr = parse_unary(tokens, position, rule)?;
+    while tokens.get(*position) == Some(&Token::And) {
+        *position += 1;
+        expr = Expr::And(
+            Box::new(expr),
+            Box::new(parse_unary(tokens, position, rule)?),
+        );
+    }
+    Ok(expr)
+}
+
+fn parse_unary(tokens: &[Token], position: &mut usize, rule: &str) -> Result<Expr, String> {
+    match tokens.get(*position) {
+        Some(Token::Not) => {
+            *position += 1;
+            Ok(Expr::Not(Box::new(parse_unary(tokens, position, rule)?)))
+        }
+        Some(Token::LParen) => {
+            *position += 1;
+            let expr = parse_or(tokens, position, rule)?;
+            if tokens.get(*position) != Some(&Token::RParen) {
+                return Err(format!("missing `)` in `{}`", rule));
+            }
+            *position += 1;
+            Ok(expr)
+        }
+        Some(Token::Word(name)) => {
+            *position += 1;
+            let Some(Token::Op(op)) = tokens.get(*position) else {
+                return Ok(Expr::Truthy(name.clone()));
+            };
+            *position += 1;
+            match tokens.get(*position) {
+                Some(Token::Word(literal) | Token::Str(literal)) => {
+                    *position += 1;
+                    Ok(Expr::Compare(name.clone(), op, literal.clone()))
+                }
+                _ => Err(format!("expected a value after `{}` in `{}`", op, rule)),
+            }
+        }
+        _ => Err(format!("expected a value name in `{}`", rule)),
+    }
+}
+
+fn eval(expr: &Expr, values: &[(&str, String)]) -> bool {
+    let lookup = |name: &str| {
+        values
+            .iter()
+            .find(|(key, _)| *key == name)
+            .map(|(_, value)| value.as_str())
+            .unwrap_or("")
+    };
+
+    match expr {
+        Expr::Truthy(name) => !matches!(lookup(name), "" | "false" | "0"),
+        Expr::Compare(name, op, literal) => compare(lookup(name), op, literal),
+        Expr::Not(inner) => !eval(inner, values),
+        Expr::And(left, right) => eval(left, values) && eval(right, values),
+        Expr::Or(left, right) => eval(left, values) || eval(right, values),
+    }
+}
+
+/// Compare numerically when both sides are numbers, otherwise as strings
+fn compare(value: &str, op: &str, literal: &str) -> bool {
+    let ordering = match (value.parse::<f64>(), literal.parse::<f64>()) {
+        (Ok(value), Ok(literal)) => value.partial_cmp(&literal),
+        _ => Some(value.cmp(literal)),
+    };
+    let Some(ordering) = ordering else {
+        return false;
+    };
+
+    match op {
+        "==" => ordering.is_eq(),
+        "!=" => ordering.is_ne(),
+        "<" => ordering.is_lt(),
+        "<=" => ordering.is_le(),
+        ">" => ordering.is_gt(),
+        ">=" => ordering.is_ge(),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(rule: &str, values: &[(&str, &str)]) -> bool {
+        let values: Vec<_> = values
+            .iter()
+            .map(|(key, value)| (*key, value.to_string()))
+            .collect();
+        Rule::parse(rule).unwrap().evaluate(&values)
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert!(check("a || b && c", &[("a", "1")]));
+        assert!(!check("(a || b) && c", &[("a", "1")]));
+        assert!(check("a && b || c", &[("c", "1")]));
+    }
+
+    #[test]
+    fn not_applies_to_the_next_operand() {
+        assert!(check("!a && b", &[("b", "1")]));
+        assert!(!check("!(a || b)", &[("b", "1")]));
+        assert!(check("!!a", &[("a", "yes")]));
+    }
+
+    #[test]
+    fn bare_names_are_truthy_unless_empty_false_or_zero() {
+        assert!(check("a", &[("a", "yes")]));
+        for value in ["", "false", "0"] {
+            assert!(!check("a", &[("a", value)]));
+        }
+        assert!(!check("missing", &[]));
+    }
+
+    #[test]
+    fn comparison_operators() {
+        let values = [("percent", "50")];
+        assert!(check("percent == 50", &values));
+        assert!(check("percent != 40", &values));
+        assert!(check("percent < 60", &values));
+        assert!(check("percent <= 50", &values));
+        assert!(check("percent > 40", &values));
+        assert!(check("percent >= 50", &values));
+        assert!(!check("percent > 50", &values));
+    }
+
+    #[test]
+    fn numbers_compare_numerically_and_strings_lexically() {
+        assert!(check("percent > 9", &[("percent", "10")]));
+        assert!(check("percent == 5", &[("percent", "5.0")]));
+        assert!(!check("name > b", &[("name", "a")]));
+        assert!(check("name < b", &[("name", "a")]));
+        assert!(check("name > 9", &[("name", "a")]));
+    }
+
+    #[test]
+    fn quoted_values_keep_operators_and_spaces() {
+        let values = [("sink", "alsa_output.usb && more")];
+        assert!(check("sink == \"alsa_output.usb && more\"", &values));
+        assert!(check("sink != 'other'", &values));
+    }
+
+    #[test]
+    fn parse_errors() {
+        for rule in [
+            "",
+            "name == \"open",
+            "a & b",
+            "a | b",
+            "(a || b",
+            "a ||",
+            "a == ",
+            "a = b",
+            "a b",
+            ")",
+        ] {
+            assert!(Rule::parse(rule).is_err(), "`{}` should not parse", rule);
+        }
+    }
+}
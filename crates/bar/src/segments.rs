@@ -0,0 +1,287 @@
+use crate::config::{SegmentStyle, SegmentsConfig};
+use crate::icons;
+use crate::overflow::Segment;
+use ratatui::{
+    layout::Alignment,
+    style::{Color, Style},
+    text::Span,
+};
+
+/// Default (open, close) glyphs for each segment style
+fn default_glyphs(style: SegmentStyle) -> (String, String) {
+    match style {
+        SegmentStyle::Plain => (String::new(), String::new()),
+        SegmentStyle::Arrow => (
+            icons::icon("segment.arrow.open"),
+            icons::icon("segment.arrow.close"),
+        ),
+        SegmentStyle::Rounded => (
+            icons::icon("segment.rounded.open"),
+            icons::icon("segment.rounded.close"),
+        ),
+        SegmentStyle::Pipe => ("│".to_string(), "│".to_string()),
+    }
+}
+
+/// Transitions sit after each segment, except in right-aligned sections where they lead it
+fn transitions_lead(alignment: Alignment) -> bool {
+    alignment == Alignment::Right
+}
+
+/// Pad a segment group's spans, paint them with its style and add its glyphs
+pub fn decorate<'a>(
+    spans: Vec<Span<'a>>,
+    style: Style,
+    config: &SegmentsConfig,
+    alignment: Alignment,
+) -> Vec<Span<'a>> {
+    let (default_open, default_close) = default_glyphs(config.style);
+    let open = config.open.clone().unwrap_or(default_open);
+    let close = config.close.clone().unwrap_or(default_close);
+    let padding = " ".repeat(config.padding);
+
+    let mut body = vec![Span::styled(padding.clone(), style)];
+    body.extend(
+        spans
+            .into_iter()
+            .map(|span| Span::styled(span.content, style.patch(span.style))),
+    );
+    body.push(Span::styled(padding, style));
+
+    // Glyphs take the segment's background as their foreground so they read as its edge
+    let edge = Style::default().fg(style.bg.unwrap_or(Color::Reset));
+    let lead = transitions_lead(alignment);
+
+    match config.style {
+        SegmentStyle::Plain => body,
+        SegmentStyle::Rounded => {
+            let mut spans = vec![Span::styled(open, edge)];
+            spans.extend(body);
+            spans.push(Span::styled(close, edge));
+            spans
+        }
+        SegmentStyle::Arrow if lead => {
+            let mut spans = vec![Span::styled(open, edge)];
+            spans.extend(body);
+            spans
+        }
+        SegmentStyle::Arrow => {
+            body.push(Span::styled(close, edge));
+            body
+        }
+        SegmentStyle::Pipe if lead => {
+            let mut spans = vec![Span::raw(open)];
+            spans.extend(body);
+            spans
+        }
+        SegmentStyle::Pipe => {
+            body.push(Span::raw(close));
+            body
+        }
+    }
+}
+
+/// Join visible segment groups to their neighbours once overflow has been resolved
+pub fn link_transitions(
+    segments: &mut [Segment<'_>],
+    config: &SegmentsConfig,
+    alignment: Alignment,
+) {
+    if !matches!(config.style, SegmentStyle::Arrow | SegmentStyle::Pipe) {
+        return;
+    }
+
+    let lead = transitions_lead(alignment);
+    let count = segments.len();
+    for i in 0..count {
+        if segments[i].background.is_none() {
+            continue;
+        }
+
+        let neighbour = if lead {
+            i.checked_sub(1)
+        } else {
+            (i + 1 < count).then_some(i + 1)
+        };
+        let neighbour_background = neighbour.and_then(|n| segments[n].background);
+
+        let spans = &mut segments[i].spans;
+        match config.style {
+            SegmentStyle::Arrow => {
+                let glyph = if lead {
+                    spans.first_mut()
+                } else {
+                    spans.last_mut()
+                };
+                if let Some(glyph) = glyph {
+                    glyph.style = glyph.style.bg(neighbour_background.unwrap_or(Color::Reset));
+                }
+            }
+            // A pipe only belongs between two things
+            _ if neighbour.is_none() => {
+                if lead {
+                    spans.remove(0);
+                } else {
+                    spans.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(style: SegmentStyle) -> SegmentsConfig {
+        SegmentsConfig {
+            style,
+            padding: 0,
+            open: Some("<".to_string()),
+            close: Some(">".to_string()),
+        }
+    }
+
+    /// A group painted with `background`, decorated as `get_bar_segments` would
+    fn group(
+        text: &'static str,
+        background: Color,
+        config: &SegmentsConfig,
+        alignment: Alignment,
+    ) -> Segment<'static> {
+        let style = Style::default().bg(background);
+        Segment {
+            key: text.to_string(),
+            spans: decorate(vec![Span::raw(text)], style, config, alignment),
+            priority: 0,
+            min_width: None,
+            max_width: None,
+            background: Some(background),
+        }
+    }
+
+    /// A component outside any group
+    fn plain(text: &'static str) -> Segment<'static> {
+        Segment {
+            key: text.to_string(),
+            spans: vec![Span::raw(text)],
+            priority: 0,
+            min_width: None,
+            max_width: None,
+            background: None,
+        }
+    }
+
+    fn text(segment: &Segment) -> String {
+        segment
+            .spans
+            .iter()
+            .map(|span| span.content.as_ref())
+            .collect()
+    }
+
+    #[test]
+    fn arrows_take_the_next_groups_background() {
+        let config = config(SegmentStyle::Arrow);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Left),
+            group("b", Color::Blue, &config, Alignment::Left),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Left);
+
+        let arrow = segments[0].spans.last().unwrap();
+        assert_eq!(arrow.content, ">");
+        assert_eq!(arrow.style, Style::default().fg(Color::Red).bg(Color::Blue));
+        // Nothing follows the last group
+        let arrow = segments[1].spans.last().unwrap();
+        assert_eq!(
+            arrow.style,
+            Style::default().fg(Color::Blue).bg(Color::Reset)
+        );
+    }
+
+    #[test]
+    fn arrows_skip_hidden_groups() {
+        // Hidden and empty groups never become segments, so the arrow meets the group after
+        let config = config(SegmentStyle::Arrow);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Left),
+            group("c", Color::Green, &config, Alignment::Left),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Left);
+        assert_eq!(
+            segments[0].spans.last().unwrap().style.bg,
+            Some(Color::Green)
+        );
+    }
+
+    #[test]
+    fn arrows_before_ungrouped_components_fade_to_the_bar() {
+        let config = config(SegmentStyle::Arrow);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Left),
+            plain("clock"),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Left);
+        assert_eq!(
+            segments[0].spans.last().unwrap().style.bg,
+            Some(Color::Reset)
+        );
+        assert_eq!(segments[1].spans, [Span::raw("clock")]);
+    }
+
+    #[test]
+    fn right_aligned_arrows_take_the_previous_groups_background() {
+        let config = config(SegmentStyle::Arrow);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Right),
+            group("b", Color::Blue, &config, Alignment::Right),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Right);
+
+        let arrow = segments[0].spans.first().unwrap();
+        assert_eq!(arrow.content, "<");
+        assert_eq!(
+            arrow.style,
+            Style::default().fg(Color::Red).bg(Color::Reset)
+        );
+        let arrow = segments[1].spans.first().unwrap();
+        assert_eq!(arrow.style, Style::default().fg(Color::Blue).bg(Color::Red));
+    }
+
+    #[test]
+    fn pipes_only_sit_between_segments() {
+        let config = config(SegmentStyle::Pipe);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Left),
+            group("b", Color::Blue, &config, Alignment::Left),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Left);
+        assert_eq!(text(&segments[0]), "a>");
+        assert_eq!(text(&segments[1]), "b");
+
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Right),
+            group("b", Color::Blue, &config, Alignment::Right),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Right);
+        assert_eq!(text(&segments[0]), "a");
+        assert_eq!(text(&segments[1]), "<b");
+    }
+
+    #[test]
+    fn rounded_segments_are_left_alone() {
+        let config = config(SegmentStyle::Rounded);
+        let mut segments = vec![
+            group("a", Color::Red, &config, Alignment::Left),
+            group("b", Color::Blue, &config, Alignment::Left),
+        ];
+        link_transitions(&mut segments, &config, Alignment::Left);
+        assert_eq!(text(&segments[0]), "<a>");
+        assert_eq!(
+            segments[0].spans.last().unwrap().style,
+            Style::default().fg(Color::Red)
+        );
+    }
+}
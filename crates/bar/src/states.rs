@@ -0,0 +1,92 @@
+/// Which way a component's value moves as it gets worse
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    /// Higher is worse, e.g. CPU usage; a state applies at or above its threshold
+    Rising,
+    /// Lower is worse, e.g. battery charge; a state applies at or below its threshold
+    Falling,
+}
+
+/// Pick the state whose threshold `value` has passed furthest.
+///
+/// The order of `states` doesn't matter, except that the first of two equal thresholds wins.
+pub fn evaluate(value: f64, direction: Direction, states: &[(String, f64)]) -> Option<&str> {
+    let passed = states.iter().filter(|(_, threshold)| match direction {
+        Direction::Rising => value >= *threshold,
+        Direction::Falling => value <= *threshold,
+    });
+
+    let further = |a: f64, b: f64| match direction {
+        Direction::Rising => a > b,
+        Direction::Falling => a < b,
+    };
+    let furthest = passed.reduce(|best, state| {
+        if further(state.1, best.1) {
+            state
+        } else {
+            best
+        }
+    });
+
+    furthest.map(|(name, _)| name.as_str())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn states(states: &[(&str, f64)]) -> Vec<(String, f64)> {
+        states
+            .iter()
+            .map(|(name, threshold)| (name.to_string(), *threshold))
+            .collect()
+    }
+
+    #[test]
+    fn falling_thresholds_match_the_battery_defaults() {
+        let battery = states(&[("warning", 25.0), ("critical", 10.0)]);
+        let evaluate = |value| evaluate(value, Direction::Falling, &battery);
+        assert_eq!(evaluate(100.0), None);
+        assert_eq!(evaluate(25.1), None);
+        assert_eq!(evaluate(25.0), Some("warning"));
+        assert_eq!(evaluate(10.1), Some("warning"));
+        assert_eq!(evaluate(10.0), Some("critical"));
+        assert_eq!(evaluate(0.0), Some("critical"));
+    }
+
+    #[test]
+    fn rising_thresholds_apply_at_their_value() {
+        let cpu = states(&[("warning", 70.0), ("critical", 90.0)]);
+        let evaluate = |value| evaluate(value, Direction::Rising, &cpu);
+        assert_eq!(evaluate(69.9), None);
+        assert_eq!(evaluate(70.0), Some("warning"));
+        assert_eq!(evaluate(89.9), Some("warning"));
+        assert_eq!(evaluate(90.0), Some("critical"));
+        assert_eq!(evaluate(100.0), Some("critical"));
+    }
+
+    #[test]
+    fn configured_order_does_not_matter() {
+        let listed = states(&[("critical", 10.0), ("warning", 25.0)]);
+        assert_eq!(evaluate(5.0, Direction::Falling, &listed), Some("critical"));
+        assert_eq!(evaluate(20.0, Direction::Falling, &listed), Some("warning"));
+
+        let listed = states(&[("critical", 90.0), ("warning", 70.0)]);
+        assert_eq!(evaluate(95.0, Direction::Rising, &listed), Some("critical"));
+        assert_eq!(evaluate(80.0, Direction::Rising, &listed), Some("warning"));
+    }
+
+    #[test]
+    fn the_first_of_equal_thresholds_wins() {
+        let listed = states(&[("low", 20.0), ("empty", 20.0)]);
+        assert_eq!(evaluate(10.0, Direction::Falling, &listed), Some("low"));
+        assert_eq!(evaluate(30.0, Direction::Rising, &listed), Some("low"));
+    }
+
+    #[test]
+    fn no_states_or_no_number_match_nothing() {
+        assert_eq!(evaluate(50.0, Direction::Rising, &[]), None);
+        let cpu = states(&[("critical", 90.0)]);
+        assert_eq!(evaluate(f64::NAN, Direction::Rising, &cpu), None);
+    }
+}
@@ -0,0 +1,197 @@
+//! Tracks windows that raised an urgent hint, from Hyprland's event socket.
+//!
+//! A window stays urgent until it is focused; a workspace is urgent while it
+//! holds an urgent window and stops being so once it is focused.
+//!
+//! Only Hyprland is supported, like the `workspaces` and `windows` components
+//! that show urgency; under other compositors nothing is ever urgent.
+
+use crate::logging;
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader};
+use std::os::unix::net::UnixStream;
+use std::path::PathBuf;
+use std::process::Command;
+use std::sync::{LazyLock, Mutex, Once};
+use std::time::Duration;
+
+/// Urgent window addresses (with their `0x` prefix) and the workspace each is on
+static URGENT: LazyLock<Mutex<HashMap<String, i32>>> = LazyLock::new(|| Mutex::new(HashMap::new()));
+
+static WATCH: Once = Once::new();
+
+/// Longest wait between attempts to reconnect to the event socket
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
+
+/// Start listening for urgency events, reconnecting when Hyprland restarts;
+/// later calls do nothing
+pub fn watch() {
+    WATCH.call_once(|| {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_none() {
+            return;
+        }
+        std::thread::spawn(|| {
+            let mut delay = Duration::from_secs(1);
+            loop {
+                let error = match event_socket_path() {
+                    Some(path) => match listen(&path, &mut delay) {
+                        Ok(()) => "event socket closed".to_string(),
+                        Err(e) => e.to_string(),
+                    },
+                    None => "event socket not found".to_string(),
+                };
+                logging::log_system_error(
+                    "urgency",
+                    &format!("Failed to read Hyprland events: {}", error),
+                );
+
+                // Events were missed while disconnected, so forget what was urgent
+                if let Ok(mut urgent) = URGENT.lock() {
+                    urgent.clear();
+                }
+                std::thread::sleep(delay);
+                delay = (delay * 2).min(MAX_RECONNECT_DELAY);
+            }
+        });
+    });
+}
+
+pub fn is_window_urgent(address: &str) -> bool {
+    URGENT
+        .lock()
+        .map(|urgent| urgent.contains_key(address))
+        .unwrap_or(false)
+}
+
+pub fn is_workspace_urgent(id: i32) -> bool {
+    URGENT
+        .lock()
+        .map(|urgent| urgent.values().any(|&workspace| workspace == id))
+        .unwrap_or(false)
+}
+
+fn event_socket_path() -> Option<PathBuf> {
+    let signature = std::env::var("HYPRLAND_INSTANCE_SIGNATURE").ok()?;
+    let runtime_dir = std::env::var("XDG_RUNTIME_DIR").unwrap_or_else(|_| "/tmp".to_string());
+
+    // Hyprland moved its sockets from /tmp/hypr into the runtime directory
+    [
+        PathBuf::from(runtime_dir).join("hypr"),
+        PathBuf::from("/tmp/hypr"),
+    ]
+    .into_iter()
+    .map(|dir| dir.join(&signature).join(".socket2.sock"))
+    .find(|path| path.exists())
+}
+
+/// Read events until the socket closes, resetting `delay` once connected
+fn listen(path: &PathBuf, delay: &mut Duration) -> std::io::Result<()> {
+    let stream = UnixStream::connect(path)?;
+    *delay = Duration::from_secs(1);
+
+    for line in BufReader::new(stream).lines() {
+        let line = line?;
+        let Some((event, data)) = line.split_once(">>") else {
+            continue;
+        };
+
+        match event {
+            "urgent" => {
+                let address = window_address(data);
+                // Ask hyprctl before taking the lock, so rendering never waits on it
+                let workspace = window_workspace(&address).unwrap_or(0);
+                if let Ok(mut urgent) = URGENT.lock() {
+                    urgent.insert(address, workspace);
+                }
+            }
+            _ => {
+                if let Ok(mut urgent) = URGENT.lock() {
+                    apply_event(&mut urgent, event, data);
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Hyprland leaves the `0x` off the addresses in its events
+fn window_address(data: &str) -> String {
+    format!("0x{}", data.trim_start_matches("0x"))
+}
+
+/// Update the urgent windows for an event that focuses, closes or moves one
+fn apply_event(urgent: &mut HashMap<String, i32>, event: &str, data: &str) {
+    match event {
+        "activewindowv2" | "closewindow" => {
+            urgent.remove(&window_address(data));
+        }
+        "workspacev2" => {
+            if let Some(id) = data
+                .split_once(',')
+                .and_then(|(id, _)| id.parse::<i32>().ok())
+            {
+                urgent.retain(|_, workspace| *workspace != id);
+            }
+        }
+        // ADDRESS,WORKSPACEID,WORKSPACENAME
+        "movewindowv2" => {
+            let mut fields = data.splitn(3, ',');
+            if let (Some(address), Some(Ok(id))) =
+                (fields.next(), fields.next().map(str::parse::<i32>))
+                && let Some(workspace) = urgent.get_mut(&window_address(address))
+            {
+                *workspace = id;
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The workspace id of the window at `address`
+fn window_workspace(address: &str) -> Option<i32> {
+    let output = Command::new("hyprctl")
+        .args(["clients", "-j"])
+        .output()
+        .ok()?;
+    let clients: Vec<serde_json::Value> = serde_json::from_slice(&output.stdout).ok()?;
+    clients
+        .iter()
+        .find(|client| client["address"] == address)
+        .and_then(|client| client["workspace"]["id"].as_i64())
+        .and_then(|id| i32::try_from(id).ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn urgent() -> HashMap<String, i32> {
+        HashMap::from([("0xabc".to_string(), 2), ("0xdef".to_string(), 3)])
+    }
+
+    #[test]
+    fn focusing_or_closing_a_window_clears_it() {
+        let mut urgent = urgent();
+        apply_event(&mut urgent, "activewindowv2", "abc");
+        apply_event(&mut urgent, "closewindow", "def");
+        assert!(urgent.is_empty());
+    }
+
+    #[test]
+    fn focusing_a_workspace_clears_its_windows() {
+        let mut urgent = urgent();
+        apply_event(&mut urgent, "workspacev2", "2,2");
+        assert_eq!(urgent, HashMap::from([("0xdef".to_string(), 3)]));
+    }
+
+    #[test]
+    fn moved_windows_take_their_urgency_along() {
+        let mut urgent = urgent();
+        apply_event(&mut urgent, "movewindowv2", "abc,5,five,with,commas");
+        apply_event(&mut urgent, "movewindowv2", "123,7,7");
+        assert_eq!(
+            urgent,
+            HashMap::from([("0xabc".to_string(), 5), ("0xdef".to_string(), 3)])
+        );
+    }
+}
@@ -1,5 +1,12 @@
-use catfood_bar::{handle_bar_cli, run_bar};
+use catfood_bar::config::Launcher;
+use catfood_bar::headless::{self, OutputFormat};
+use catfood_bar::ipc::Request;
+use catfood_bar::{
+    handle_bar_cli, print_schema, print_status, restart_bars, run_bar, send_message, stop_bar,
+    stop_for_restart,
+};
 use clap::{Parser, Subcommand};
+use std::time::Duration;
 
 #[derive(Parser)]
 #[command(name = "catfood")]
@@ -13,9 +20,49 @@ pub struct Cli {
 pub enum Commands {
     /// Run the system bar
     Bar {
+        #[command(subcommand)]
+        command: Option<BarCommands>,
+
         /// Run without spawning in a kitten panel
         #[arg(long = "no-kitten")]
         no_kitten: bool,
+
+        /// What to start the bar in, overriding the config's `panel.launcher`
+        #[arg(long, value_enum)]
+        launcher: Option<Launcher>,
+
+        /// What the terminal running the bar was started in, set by the launcher for `restart`
+        #[arg(long, value_enum, hide = true)]
+        launched_by: Option<Launcher>,
+
+        /// Config profile to apply (defaults to one matching the hostname)
+        #[arg(long)]
+        profile: Option<String>,
+
+        /// Monitor this bar runs on, selecting its `outputs` overrides
+        #[arg(long = "monitor", value_name = "MONITOR")]
+        output: Option<String>,
+
+        /// Write to stdout in this format instead of drawing into a terminal
+        #[arg(long = "output", visible_alias = "format", value_enum)]
+        format: Option<OutputFormat>,
+
+        /// Update every component once, write a single status line and exit (defaults to text)
+        #[arg(long)]
+        once: bool,
+
+        /// Seconds between status lines written with --output
+        #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: u64,
+    },
+    /// Send a command to the running bar
+    Msg {
+        #[command(subcommand)]
+        request: Request,
+
+        /// Only message the bar on this monitor (defaults to every running bar)
+        #[arg(long = "monitor", value_name = "MONITOR")]
+        output: Option<String>,
     },
     /// Run the menu system
     Menu {
@@ -29,18 +76,101 @@ pub enum Commands {
     },
 }
 
+#[derive(Subcommand)]
+pub enum BarCommands {
+    /// Print the JSON Schema for bar.json
+    Schema,
+    /// Stop every running bar
+    Stop,
+    /// Stop every running bar and start each again
+    Restart,
+    /// Show whether the bar is running
+    Status,
+}
+
 fn main() -> color_eyre::Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Bar { no_kitten } => {
+        Commands::Bar {
+            command: Some(BarCommands::Schema),
+            ..
+        } => {
+            print_schema()?;
+        }
+        Commands::Bar {
+            command: Some(BarCommands::Stop),
+            ..
+        } => {
+            if stop_bar()? == 0 {
+                println!("catfood-bar is not running");
+            }
+        }
+        Commands::Bar {
+            command: Some(BarCommands::Status),
+            ..
+        } => {
+            print_status()?;
+        }
+        Commands::Bar {
+            command: command @ (None | Some(BarCommands::Restart)),
+            no_kitten,
+            launcher,
+            launched_by,
+            profile,
+            output,
+            format,
+            once,
+            interval,
+        } => {
+            if format.is_some() || once {
+                headless::run(
+                    format.unwrap_or(OutputFormat::Text),
+                    once,
+                    Duration::from_secs(interval),
+                    profile.as_deref(),
+                    output.as_deref(),
+                )?;
+                return Ok(());
+            }
+
+            // Start each bar the way it was started before, unless told otherwise; with none
+            // running, start the bar as usual
+            if command.is_some() {
+                let previous = stop_for_restart()?;
+                if !previous.is_empty() {
+                    let restarted = restart_bars(
+                        previous,
+                        no_kitten,
+                        launcher,
+                        profile.as_deref(),
+                        output.as_deref(),
+                    )?;
+                    if let Some(here) = restarted {
+                        run_bar(
+                            here.profile.as_deref(),
+                            here.output.as_deref(),
+                            Launcher::Current,
+                        )?;
+                    }
+                    return Ok(());
+                }
+            }
+
             // Handle common CLI logic
-            if handle_bar_cli(no_kitten) {
+            if handle_bar_cli(no_kitten, launcher, profile.as_deref(), output.as_deref()) {
                 return Ok(()); // Process spawned in panel and exited
             }
 
             // Run directly with existing behavior (--no-kitten case)
-            run_bar()?;
+            run_bar(
+                profile.as_deref(),
+                output.as_deref(),
+                launched_by.unwrap_or(Launcher::Current),
+            )?;
+        }
+        Commands::Msg { request, output } => {
+            send_message(&request, output.as_deref())?;
         }
         Commands::Menu { categories: _ } => {
             println!("Menu feature coming soon!");